@@ -1,13 +1,58 @@
-pub const ANT_COIN: &str = "FLitGKEPBvBNqPVZbfgRPR5fwcsgSrRv6BDZjxRRFhUC";
 pub const PRECISION: u32 = 1000;
-pub const ANT_FOOD_TOKEN_ID: &str = "4JtesASQCh1ZYDdvCpgpMG5WLxMKyGAVt4tS4QS9L8Np";
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
 pub const STAKE_FEE_AMOUNT: u64 = 2;    // 100 ANT coin
-pub const MAX_AMOUNT_FOR_STAKE: u64 = 90_000_000_000; // 900k ANT coin
-pub const CYCLE_STAKED_AMOUNT: u64 = 30_000_000_000; // 300k ANT coin
-pub const CYCLE_TIMESTAMP: u32 = 60 * 60 * 24;  // 24 hours
+pub const PER_USER_CAP: u64 = 90_000_000_000; // 900k ANT coin
+pub const POOL_CAP: u64 = 900_000_000_000; // 9M ANT coin
+pub const REWARD_DURATION: i64 = 60 * 60 * 24 * 7;  // 7 days
+pub const MAX_LOCK_TIERS: usize = 4;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_REWARD_STREAMS: usize = 3;
+pub const MAX_UNSTAKE_TRANCHES: usize = 8;
+pub const ROLE_FEE_MANAGER: u8 = 1 << 0;
+pub const ROLE_PARAM_MANAGER: u8 = 1 << 1;
+pub const ROLE_REWARD_DEPOSITOR: u8 = 1 << 2;
+pub const ROLE_MINT_ADMIN: u8 = 1 << 3;
+pub const ROLE_PAUSER: u8 = 1 << 4;
+pub const ROLE_ALL: u8 = ROLE_FEE_MANAGER | ROLE_PARAM_MANAGER | ROLE_REWARD_DEPOSITOR | ROLE_MINT_ADMIN | ROLE_PAUSER;
+pub const PAUSE_STAKE: u8 = 1 << 0;
+pub const PAUSE_UNSTAKE: u8 = 1 << 1;
+pub const PAUSE_CLAIM: u8 = 1 << 2;
+pub const PAUSE_DEPOSIT: u8 = 1 << 3;
+pub const PAUSE_ADMIN_WITHDRAW: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 = PAUSE_STAKE | PAUSE_UNSTAKE | PAUSE_CLAIM | PAUSE_DEPOSIT | PAUSE_ADMIN_WITHDRAW;
+pub const CHANGE_ANT_COIN: u8 = 0;
+pub const CHANGE_ANT_FOOD_TOKEN: u8 = 1;
+pub const CHANGE_PER_USER_CAP: u8 = 2;
+pub const CHANGE_TIMELOCK_DELAY: u8 = 3;
+pub const CHANGE_POOL_CAP: u8 = 4;
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+pub const MAX_PRICE_AGE: i64 = 60;   // 1 minute
+pub const MAX_PRICE_CONF_BPS: u32 = 200;    // 2%
+pub const MAX_PRICE_DECIMALS: u32 = 18;
+
+// Pyth price account layout
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+pub const PYTH_MAGIC_OFFSET: usize = 0;
+pub const PYTH_ACCOUNT_TYPE_OFFSET: usize = 8;
+pub const PYTH_EXPO_OFFSET: usize = 20;
+pub const PYTH_TIMESTAMP_OFFSET: usize = 96;
+pub const PYTH_AGG_PRICE_OFFSET: usize = 208;
+pub const PYTH_AGG_CONF_OFFSET: usize = 216;
+pub const PYTH_AGG_STATUS_OFFSET: usize = 224;
+pub const PYTH_PRICE_ACCOUNT_SIZE: usize = 240;
 
 pub const GLOBAL_STATE_SEED: &[u8] = b"GLOBAL-STATE-SEED";
-pub const MINTER_STATE_SEED: &[u8] = b"MINTER-STATE-SEED";
+pub const POOL_SEED: &[u8] = b"POOL-SEED";
+pub const ROLE_STATE_SEED: &[u8] = b"ROLE-STATE-SEED";
 pub const STAKED_INFO_SEED: &[u8] = b"STAKED-INFO-SEED";
+pub const USER_POSITIONS_SEED: &[u8] = b"USER-POSITIONS-SEED";
 pub const VAULT_SEED: &[u8] = b"VAULT-SEED";
-pub const TOKEN_VAULT_SEED: &[u8] = b"TOKEN-VAULT-SEED";
\ No newline at end of file
+pub const TOKEN_VAULT_SEED: &[u8] = b"TOKEN-VAULT-SEED";
+pub const TREASURY_SEED: &[u8] = b"TREASURY-SEED";
+pub const UNSTAKE_REQUEST_SEED: &[u8] = b"UNSTAKE-REQUEST-SEED";
+pub const PENDING_CHANGE_SEED: &[u8] = b"PENDING-CHANGE-SEED";
+pub const MULTISIG_SEED: &[u8] = b"MULTISIG-SEED";
+pub const MULTISIG_SIGNER_SEED: &[u8] = b"MULTISIG-SIGNER-SEED";
+pub const PROPOSAL_SEED: &[u8] = b"PROPOSAL-SEED";
\ No newline at end of file
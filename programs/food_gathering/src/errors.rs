@@ -19,4 +19,121 @@ pub enum FoodGatheringError {
 
     #[msg("FoodGathering: You didn't stake any amount of ant coins")]
     NotStaked,
+
+    #[msg("FoodGathering: unstake amount exceeds the staked amount")]
+    InsufficientStakedAmount,
+
+    #[msg("FoodGathering: reward token is not the staked ant coin, so it can't be compounded")]
+    RewardMintMismatch,
+
+    #[msg("FoodGathering: lock tier doesn't exist or is disabled")]
+    InvalidLockTier,
+
+    #[msg("FoodGathering: staked position already uses another lock tier")]
+    LockTierMismatch,
+
+    #[msg("FoodGathering: staked ant coins are still locked")]
+    StillLocked,
+
+    #[msg("FoodGathering: leaving a locked position early requires accepting the penalty")]
+    PenaltyNotAccepted,
+
+    #[msg("FoodGathering: treasury account is required to keep the penalty")]
+    MissingTreasuryAccount,
+
+    #[msg("FoodGathering: unstaking needs a request_unstake while a cooldown is set")]
+    CooldownRequired,
+
+    #[msg("FoodGathering: too many unstake requests in flight")]
+    TooManyUnstakeRequests,
+
+    #[msg("FoodGathering: no unstake request is ready for withdrawal")]
+    NothingToWithdraw,
+
+    #[msg("FoodGathering: cooldown can't be negative")]
+    InvalidCooldown,
+
+    #[msg("FoodGathering: price feed account is required")]
+    MissingPriceFeed,
+
+    #[msg("FoodGathering: no usable ant coin price")]
+    PriceUnavailable,
+
+    #[msg("FoodGathering: Not allowed role")]
+    NotAllowedRole,
+
+    #[msg("FoodGathering: unknown or invalid change")]
+    InvalidChange,
+
+    #[msg("FoodGathering: mint account is required for this change")]
+    MissingMint,
+
+    #[msg("FoodGathering: queued change can't be executed yet")]
+    ChangeNotReady,
+
+    #[msg("FoodGathering: amount exceeds the surplus above staker liabilities")]
+    InsufficientSurplus,
+
+    #[msg("FoodGathering: unknown pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("FoodGathering: position still holds stake or reward")]
+    PositionNotEmpty,
+
+    #[msg("FoodGathering: position id is neither an existing nor the next position")]
+    InvalidPositionId,
+
+    #[msg("FoodGathering: invalid multisig signers or threshold")]
+    InvalidMultisig,
+
+    #[msg("FoodGathering: not a signer of the multisig")]
+    NotMultisigSigner,
+
+    #[msg("FoodGathering: multisig signers changed since the proposal was created")]
+    StaleProposal,
+
+    #[msg("FoodGathering: proposal was already executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("FoodGathering: proposal doesn't have enough approvals")]
+    NotEnoughApprovals,
+
+    #[msg("FoodGathering: stake amount exceeds the maximum per user")]
+    UserStakingCapAttained,
+
+    #[msg("FoodGathering: deposit is too small for the reward duration")]
+    RewardRateTooLow,
+
+    #[msg("FoodGathering: reward period is still running")]
+    RewardPeriodActive,
+
+    #[msg("FoodGathering: reward duration must be positive")]
+    InvalidRewardDuration,
+
+    #[msg("FoodGathering: no reward is owed")]
+    NothingOwed,
+
+    #[msg("FoodGathering: reward vault can't pay any owed reward yet")]
+    InsufficientRewardBalance,
+
+    #[msg("FoodGathering: reward stream accounts come as (vault, token account, mint, token program) groups")]
+    MissingRewardStreamAccounts,
+
+    #[msg("FoodGathering: reward stream account doesn't match the stream")]
+    InvalidRewardStreamAccount,
+
+    #[msg("FoodGathering: no free reward stream slot")]
+    TooManyRewardStreams,
+
+    #[msg("FoodGathering: invalid or retired reward stream")]
+    InvalidRewardStream,
+
+    #[msg("FoodGathering: transfer fee of the mint can't be computed")]
+    InvalidTransferFee,
+
+    #[msg("FoodGathering: pool still holds stakes or rewards")]
+    PoolNotEmpty,
+
+    #[msg("FoodGathering: unknown role bits")]
+    InvalidRoles,
 }
@@ -1,14 +1,235 @@
 use anchor_lang::prelude::{Pubkey, *};
+use crate::constants::MAX_REWARD_STREAMS;
 
 #[event]
 pub struct FoodGatheringStaked {
+    pub pool: Pubkey,
     pub staker: Pubkey,
     pub antc_stake_amount: u64,
+    pub lock_tier: u8,
+    pub unlock_timestamp: i64,
+    pub pool_total_staked: u64,
+    pub pool_cap: u64,
 }
 
 #[event]
 pub struct FoodGatheringUnStaked {
+    pub pool: Pubkey,
     pub staker: Pubkey,
     pub antc_stake_amount: u64,
     pub reward_ant_food_amount: u64,
+    pub penalty_amount: u64,
+    pub pool_total_staked: u64,
+    pub pool_cap: u64,
 }
+
+
+#[event]
+pub struct FoodGatheringRewardClaimed {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub reward_ant_food_amount: u64,
+}
+
+#[event]
+pub struct FoodGatheringRewardShortfall {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub shortfall: u64,
+    pub owed_reward: u64,
+}
+
+#[event]
+pub struct FoodGatheringStreamRewardsClaimed {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub reward_amounts: [u64; MAX_REWARD_STREAMS],
+}
+
+#[event]
+pub struct FoodGatheringCompounded {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub antc_compound_amount: u64,
+    pub antc_stake_amount: u64,
+    pub pool_total_staked: u64,
+    pub pool_cap: u64,
+}
+
+#[event]
+pub struct FoodGatheringPoolCreated {
+    pub pool: Pubkey,
+    pub pool_id: u64,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+}
+
+#[event]
+pub struct FoodGatheringLockTierSet {
+    pub pool: Pubkey,
+    pub lock_tier: u8,
+    pub lock_duration: i64,
+    pub multiplier_bps: u32,
+    pub early_unlock_penalty_bps: u32,
+    pub is_enabled: bool,
+}
+
+#[event]
+pub struct FoodGatheringUnstakeRequested {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub antc_unstake_amount: u64,
+    pub penalty_amount: u64,
+    pub unlock_timestamp: i64,
+    pub pool_total_staked: u64,
+    pub pool_cap: u64,
+}
+
+#[event]
+pub struct FoodGatheringUnstakeWithdrawn {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub antc_unstake_amount: u64,
+}
+
+#[event]
+pub struct FoodGatheringOwnershipProposed {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct FoodGatheringOwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct FoodGatheringRoleGranted {
+    pub authority: Pubkey,
+    pub granted_roles: u8,
+    pub roles: u8,
+}
+
+#[event]
+pub struct FoodGatheringRoleRevoked {
+    pub authority: Pubkey,
+    pub revoked_roles: u8,
+    pub roles: u8,
+}
+
+#[event]
+pub struct FoodGatheringChangeQueued {
+    pub pool: Pubkey,
+    pub kind: u8,
+    pub new_key: Pubkey,
+    pub new_value: u64,
+    pub eta: i64,
+}
+
+#[event]
+pub struct FoodGatheringChangeExecuted {
+    pub pool: Pubkey,
+    pub kind: u8,
+    pub new_key: Pubkey,
+    pub new_value: u64,
+}
+
+#[event]
+pub struct FoodGatheringChangeCancelled {
+    pub pool: Pubkey,
+    pub kind: u8,
+}
+
+#[event]
+pub struct FoodGatheringRewardPeriodFunded {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub reward_rate: u64,
+    pub period_finish: i64,
+}
+
+#[event]
+pub struct FoodGatheringRewardStreamAdded {
+    pub pool: Pubkey,
+    pub stream_index: u8,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct FoodGatheringRewardStreamRetired {
+    pub pool: Pubkey,
+    pub stream_index: u8,
+}
+
+#[event]
+pub struct FoodGatheringRewardStreamFunded {
+    pub pool: Pubkey,
+    pub stream_index: u8,
+    pub amount: u64,
+    pub reward_rate: u64,
+    pub period_finish: i64,
+}
+
+#[event]
+pub struct FoodGatheringPauseStateChanged {
+    pub old_paused_flags: u8,
+    pub paused_flags: u8,
+}
+
+#[event]
+pub struct FoodGatheringEmergencyWithdrawn {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub antc_stake_amount: u64,
+    pub forfeited_reward: u64,
+    pub pool_total_staked: u64,
+    pub pool_cap: u64,
+}
+
+#[event]
+pub struct FoodGatheringTreasuryWithdrawal {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub remaining_surplus: u64,
+}
+
+#[event]
+pub struct FoodGatheringMultisigCreated {
+    pub multisig: Pubkey,
+    pub multisig_signer: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct FoodGatheringMultisigSignersChanged {
+    pub multisig: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct FoodGatheringProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct FoodGatheringProposalApproved {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub approver: Pubkey,
+    pub approval_count: u8,
+}
+
+#[event]
+pub struct FoodGatheringProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+}
\ No newline at end of file
@@ -1,33 +1,23 @@
 use crate::{constants::*, errors::*, state::*, events::*, utils::*};
 use anchor_lang::prelude::Account;
 use anchor_lang::prelude::*;
-use solana_program::{program::invoke_signed, program::invoke, system_instruction};
+use solana_program::{program::invoke_signed, program::invoke, system_instruction, instruction::{AccountMeta, Instruction}};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount, Burn, burn, Transfer, transfer},
+    token_interface::{Mint, TokenAccount, TokenInterface, Burn, burn, TransferChecked, transfer_checked},
 };
 // use mpl_token_metadata::types::DataV2;
 use std::mem::size_of;
 
-#[access_control(ctx.accounts.validate())]
-pub fn initialize(ctx: Context<Initialize>, new_owner: Pubkey, antc_price: u64, antc_expo: u64) -> Result<()> {
+pub fn initialize(ctx: Context<Initialize>, new_owner: Pubkey) -> Result<()> {
     let accts = ctx.accounts;
     accts.global_state.is_initialized = 1;
-    accts.global_state.is_paused = false;
+    accts.global_state.paused_flags = 0;
     accts.global_state.owner = new_owner;
     accts.global_state.vault = accts.vault.key();
-    accts.global_state.ant_coin = Pubkey::try_from(ANT_COIN).unwrap();
-    accts.global_state.precision = PRECISION;
-    accts.global_state.ant_food_token = Pubkey::try_from(ANT_FOOD_TOKEN_ID).unwrap();
-    accts.global_state.stake_fee_amount = STAKE_FEE_AMOUNT;
-    accts.global_state.max_amount_for_stake = MAX_AMOUNT_FOR_STAKE;
-    accts.global_state.cycle_staked_amount = CYCLE_STAKED_AMOUNT;
-    accts.global_state.cycle_timestamp = CYCLE_TIMESTAMP;
-    accts.global_state.antc_price = antc_price;
-    accts.global_state.antc_expo = antc_expo;
-
-    accts.minter.minter_key = new_owner;
-    accts.minter.is_minter = true;
+
+    accts.role.authority = new_owner;
+    accts.role.roles = ROLE_ALL;
 
     let rent = Rent::default();
     let required_lamports = rent
@@ -51,34 +41,107 @@ pub fn initialize(ctx: Context<Initialize>, new_owner: Pubkey, antc_price: u64,
     Ok(())
 }
 
-pub fn stake(ctx: Context<Stake>, antc_amount: u64) -> Result<()> {
+pub fn create_pool(ctx: Context<CreatePool>, pool_id: u64, antc_price: u64, antc_expo: u64, timelock_delay: i64) -> Result<()> {
     let accts = ctx.accounts;
+    require!(timelock_delay >= 0, FoodGatheringError::InvalidChange);
+
+    accts.pool.pool_id = pool_id;
+    accts.pool.global_state = accts.global_state.key();
+    accts.pool.stake_mint = accts.stake_mint.key();
+    accts.pool.reward_mint = accts.reward_mint.key();
+    accts.pool.is_reward_stake_mint = accts.pool.reward_mint == accts.pool.stake_mint;
+    accts.pool.precision = PRECISION;
+    accts.pool.stake_fee_amount = STAKE_FEE_AMOUNT;
+    accts.pool.per_user_cap = PER_USER_CAP;
+    accts.pool.pool_cap = POOL_CAP;
+    accts.pool.reward_duration = REWARD_DURATION;
+    accts.pool.antc_price = antc_price;
+    accts.pool.antc_expo = antc_expo;
+    accts.pool.max_price_age = MAX_PRICE_AGE;
+    accts.pool.max_price_conf_bps = MAX_PRICE_CONF_BPS;
+    accts.pool.timelock_delay = timelock_delay;
+    accts.pool.last_update_ts = Clock::get()?.unix_timestamp;
+    // other tiers stay disabled until the owner configures them
+    accts.pool.lock_tiers[0] = LockTier {
+        lock_duration: 0,
+        multiplier_bps: BPS_DENOMINATOR as u32,
+        early_unlock_penalty_bps: 0,
+        is_enabled: true,
+    };
+
+    emit!(FoodGatheringPoolCreated {
+        pool: accts.pool.key(),
+        pool_id,
+        stake_mint: accts.pool.stake_mint,
+        reward_mint: accts.pool.reward_mint
+    });
 
-    require!(accts.staked_info.staked_amount + antc_amount < accts.global_state.max_amount_for_stake, FoodGatheringError::MaxStakingAmountAttained);
+    Ok(())
+}
+
+pub fn stake(ctx: Context<Stake>, antc_amount: u64, lock_tier: u8, position_id: u64) -> Result<()> {
+    let accts = ctx.accounts;
+
+    // either an existing position of the user or the next one
+    require!(position_id <= accts.user_positions.position_count, FoodGatheringError::InvalidPositionId);
+    require!((lock_tier as usize) < MAX_LOCK_TIERS, FoodGatheringError::InvalidLockTier);
+    let tier = accts.pool.lock_tiers[lock_tier as usize];
+    require!(tier.is_enabled, FoodGatheringError::InvalidLockTier);
+    require!(
+        accts.staked_info.staked_amount == 0 || accts.staked_info.lock_tier == lock_tier,
+        FoodGatheringError::LockTierMismatch
+    );
 
-    let mut pending_reward = 0;
-    let now = Clock::get()?.unix_timestamp;
-    if accts.staked_info.staked_amount > 0 {
-        pending_reward = _get_pending_reward(&accts.global_state, &accts.staked_info).unwrap();
-    }
-    accts.staked_info.reward_debt += pending_reward;
-    accts.staked_info.staked_amount += antc_amount;
-    accts.staked_info.staked_timestamp = now;
-    
     // transfer antc coin
+    let vault_balance_before = accts.ant_coin_vault_account.amount;
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
+        TransferChecked {
             from: accts.user_ant_coin_account.to_account_info(),
+            mint: accts.ant_coin.to_account_info(),
             to: accts.ant_coin_vault_account.to_account_info(),
             authority: accts.user.to_account_info(),
         },
     );
-    transfer(cpi_ctx, antc_amount)?;
+    transfer_checked(cpi_ctx, antc_amount, accts.ant_coin.decimals)?;
+
+    // only what actually reached the vault is staked, a fee-on-transfer mint keeps part of antc_amount
+    accts.ant_coin_vault_account.reload()?;
+    let staked_amount = accts.ant_coin_vault_account.amount.checked_sub(vault_balance_before).unwrap();
+
+    require!(accts.user_positions.staked_amount + staked_amount <= accts.pool.per_user_cap, FoodGatheringError::UserStakingCapAttained);
+    require!(accts.pool.total_staked + staked_amount <= accts.pool.pool_cap, FoodGatheringError::MaxStakingAmountAttained);
+
+    _update_pool(&mut accts.pool)?;
+    _settle_stream_rewards(&accts.pool, &mut accts.staked_info);
 
-    // burn antc coin
+    let now = Clock::get()?.unix_timestamp;
+    let pending_reward = _get_pending_reward(&accts.pool, &accts.staked_info).unwrap();
+    accts.staked_info.unclaimed_reward = pending_reward;
+    // adding to a position restarts its lock with the current terms of the tier
+    accts.staked_info.staked_timestamp = now;
+    accts.staked_info.lock_tier = lock_tier;
+    accts.staked_info.lock_duration = tier.lock_duration;
+    accts.staked_info.multiplier_bps = tier.multiplier_bps;
+    accts.staked_info.early_unlock_penalty_bps = tier.early_unlock_penalty_bps;
+    let new_staked_amount = accts.staked_info.staked_amount + staked_amount;
+    _update_staked_amount(&mut accts.pool, &mut accts.staked_info, new_staked_amount);
+    accts.user_positions.staked_amount += staked_amount;
+    if position_id == accts.user_positions.position_count {
+        accts.user_positions.position_count += 1;
+    }
+    accts.staked_info.position_id = position_id;
+
+    // burn antc coin, priced from the feed when it's set and healthy, otherwise from the manual price
+    let price_feed = accts.price_feed.as_ref().map(|price_feed| price_feed.to_account_info());
+    let (antc_price, antc_expo) = _get_antc_price(&accts.pool, price_feed.as_ref(), now)?;
     let decimal = accts.ant_coin.decimals;
-    let burn_amount = accts.global_state.stake_fee_amount * 10_u64.pow(u32::try_from(decimal).unwrap()) / accts.global_state.antc_price * accts.global_state.antc_expo;
+    // multiply before dividing, a feed price carries many more digits than the manual one
+    let burn_amount = (accts.pool.stake_fee_amount as u128)
+        .checked_mul(10_u128.pow(decimal as u32)).unwrap()
+        .checked_mul(antc_expo as u128).unwrap()
+        .checked_div(antc_price as u128).unwrap();
+    let burn_amount = u64::try_from(burn_amount).unwrap();
 
     let cpi_context = CpiContext::new(
         accts.token_program.to_account_info(),
@@ -92,136 +155,769 @@ pub fn stake(ctx: Context<Stake>, antc_amount: u64) -> Result<()> {
     burn(cpi_context, burn_amount)?;
 
     emit!(FoodGatheringStaked {
+        pool: accts.pool.key(),
         staker: accts.user.key(),
-        antc_stake_amount: antc_amount
+        antc_stake_amount: staked_amount,
+        lock_tier,
+        unlock_timestamp: now + tier.lock_duration,
+        pool_total_staked: accts.pool.total_staked,
+        pool_cap: accts.pool.pool_cap
     });
 
     Ok(())
 }
 
-pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+pub fn unstake<'info>(ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>, antc_amount: u64, accept_penalty: bool) -> Result<()> {
     let accts = ctx.accounts;
 
+    require!(accts.pool.unstake_cooldown == 0, FoodGatheringError::CooldownRequired);
     require!(accts.staked_info.staked_amount > 0, FoodGatheringError::NotStaked);
+    require!(antc_amount > 0 && antc_amount <= accts.staked_info.staked_amount, FoodGatheringError::InsufficientStakedAmount);
 
-    let pending_reward = _get_pending_reward(&accts.global_state, &accts.staked_info).unwrap();
-    
-    let binding = accts.global_state.owner;
-    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED, binding.as_ref()], ctx.program_id);
-    let vault_seeds = &[GLOBAL_STATE_SEED, binding.as_ref(), &[bump]];
+    let now = Clock::get()?.unix_timestamp;
+    let penalty_amount = _get_exit_penalty(&accts.staked_info, antc_amount, accept_penalty, now)?;
+
+    _update_pool(&mut accts.pool)?;
+    _settle_stream_rewards(&accts.pool, &mut accts.staked_info);
+
+    // partner reward streams are paid from the accounts passed after the named ones,
+    // a stream left out keeps its reward unclaimed and doesn't hold the principal back
+    let reward_amount = _settle_and_pay_reward(
+        &mut accts.pool,
+        &mut accts.staked_info,
+        accts.user.key(),
+        &accts.global_state,
+        &accts.ant_food_token,
+        accts.ant_food_token_vault_account.as_deref(),
+        accts.user_ant_food_token_account.to_account_info(),
+        accts.reward_token_program.to_account_info(),
+        ctx.remaining_accounts
+    )?;
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
     let signer = &[&vault_seeds[..]];
 
-    // transfer ant food token
-    if pending_reward > 0 {
-        let cpi_ctx = CpiContext::new(
-            accts.token_program.to_account_info(),
-            Transfer {
-                from: accts.ant_food_token_vault_account.to_account_info().clone(),
-                to: accts.user_ant_food_token_account.to_account_info().clone(),
-                authority: accts.global_state.to_account_info().clone(),
-            },
-        );
-        transfer(
-            cpi_ctx.with_signer(signer),
-            pending_reward.checked_div(accts.global_state.precision as u64).unwrap()
-        )?;
+    // transfer antc coin
+    let cpi_ctx = CpiContext::new(
+        accts.stake_token_program.to_account_info(),
+        TransferChecked {
+            from: accts.ant_coin_vault_account.to_account_info(),
+            mint: accts.ant_coin.to_account_info(),
+            to: accts.user_ant_coin_account.to_account_info(),
+            authority: accts.global_state.to_account_info(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), antc_amount - penalty_amount, accts.ant_coin.decimals)?;
+
+    _take_early_unlock_penalty(
+        penalty_amount,
+        &accts.global_state,
+        &accts.ant_coin,
+        accts.ant_coin_vault_account.to_account_info(),
+        accts.treasury_account.as_ref().map(|treasury_account| treasury_account.to_account_info()),
+        accts.stake_token_program.to_account_info(),
+        signer
+    )?;
+
+    // the reward earned so far is settled, so the rest of the stake starts accruing from now on
+    let staked_amount = accts.staked_info.staked_amount - antc_amount;
+    _update_staked_amount(&mut accts.pool, &mut accts.staked_info, staked_amount);
+    accts.user_positions.staked_amount -= antc_amount;
+
+    emit!(FoodGatheringUnStaked {
+        pool: accts.pool.key(),
+        staker: accts.user.key(),
+        antc_stake_amount: antc_amount,
+        reward_ant_food_amount: reward_amount,
+        penalty_amount,
+        pool_total_staked: accts.pool.total_staked,
+        pool_cap: accts.pool.pool_cap
+    });
+
+    // a fully exited position gives its rent back, the reward left below the precision is dropped
+    // the account can be created again by the next stake, a position with an IOU stays open
+    if accts.staked_info.staked_amount == 0
+        && accts.staked_info.owed_reward == 0
+        && !_has_stream_rewards(&accts.pool, &accts.staked_info)
+    {
+        accts.pool.total_reward_owed = accts.pool.total_reward_owed.saturating_sub(accts.staked_info.unclaimed_reward as u128);
+        _forfeit_stream_rewards(&mut accts.pool, &mut accts.staked_info);
+        accts.staked_info.close(accts.user.to_account_info())?;
+    }
+
+    Ok(())
+}
+pub fn request_unstake(ctx: Context<RequestUnstake>, antc_amount: u64, accept_penalty: bool) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(accts.staked_info.staked_amount > 0, FoodGatheringError::NotStaked);
+    require!(antc_amount > 0 && antc_amount <= accts.staked_info.staked_amount, FoodGatheringError::InsufficientStakedAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let penalty_amount = _get_exit_penalty(&accts.staked_info, antc_amount, accept_penalty, now)?;
+
+    let unlock_timestamp = now + accts.pool.unstake_cooldown;
+    let tranche = accts.unstake_requests.tranches
+        .iter_mut()
+        .find(|tranche| tranche.amount == 0)
+        .ok_or(FoodGatheringError::TooManyUnstakeRequests)?;
+    tranche.amount = antc_amount - penalty_amount;
+    tranche.unlock_timestamp = unlock_timestamp;
+
+    _update_pool(&mut accts.pool)?;
+    _settle_stream_rewards(&accts.pool, &mut accts.staked_info);
+
+    // the requested amount stops accruing, the reward earned so far stays claimable
+    let pending_reward = _get_pending_reward(&accts.pool, &accts.staked_info).unwrap();
+    accts.staked_info.unclaimed_reward = pending_reward;
+    let staked_amount = accts.staked_info.staked_amount - antc_amount;
+    _update_staked_amount(&mut accts.pool, &mut accts.staked_info, staked_amount);
+    accts.user_positions.staked_amount -= antc_amount;
+    accts.pool.total_unbonding += antc_amount - penalty_amount;
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let signer = &[&vault_seeds[..]];
+
+    _take_early_unlock_penalty(
+        penalty_amount,
+        &accts.global_state,
+        &accts.ant_coin,
+        accts.ant_coin_vault_account.to_account_info(),
+        accts.treasury_account.as_ref().map(|treasury_account| treasury_account.to_account_info()),
+        accts.token_program.to_account_info(),
+        signer
+    )?;
+
+    emit!(FoodGatheringUnstakeRequested {
+        pool: accts.pool.key(),
+        staker: accts.user.key(),
+        antc_unstake_amount: antc_amount - penalty_amount,
+        penalty_amount,
+        unlock_timestamp,
+        pool_total_staked: accts.pool.total_staked,
+        pool_cap: accts.pool.pool_cap
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    // while unstaking is paused the cooldown is waived like the lock in emergency_withdraw,
+    // so requested principal isn't held through an incident
+    let now = Clock::get()?.unix_timestamp;
+    let is_emergency = accts.global_state.is_paused(PAUSE_UNSTAKE);
+    let mut antc_amount = 0;
+    for tranche in accts.unstake_requests.tranches.iter_mut() {
+        if tranche.amount > 0 && (is_emergency || tranche.unlock_timestamp <= now) {
+            antc_amount += tranche.amount;
+            *tranche = UnstakeTranche::default();
+        }
     }
+    require!(antc_amount > 0, FoodGatheringError::NothingToWithdraw);
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let signer = &[&vault_seeds[..]];
+
     // transfer antc coin
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
+        TransferChecked {
             from: accts.ant_coin_vault_account.to_account_info(),
+            mint: accts.ant_coin.to_account_info(),
             to: accts.user_ant_coin_account.to_account_info(),
             authority: accts.global_state.to_account_info(),
         },
     );
-    transfer(
-        cpi_ctx.with_signer(signer),
-        accts.staked_info.staked_amount
+    transfer_checked(cpi_ctx.with_signer(signer), antc_amount, accts.ant_coin.decimals)?;
+
+    accts.pool.total_unbonding -= antc_amount;
+
+    emit!(FoodGatheringUnstakeWithdrawn {
+        pool: accts.pool.key(),
+        staker: accts.user.key(),
+        antc_unstake_amount: antc_amount
+    });
+
+    Ok(())
+}
+
+pub fn claim_reward<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimReward<'info>>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    // reward harvested by request_unstake can still be claimed after the whole stake left
+    require!(
+        accts.staked_info.staked_amount > 0
+            || accts.staked_info.unclaimed_reward > 0
+            || _has_stream_rewards(&accts.pool, &accts.staked_info),
+        FoodGatheringError::NotStaked
+    );
+
+    _update_pool(&mut accts.pool)?;
+    _settle_stream_rewards(&accts.pool, &mut accts.staked_info);
+
+    // partner reward streams are paid from the accounts passed after the named ones
+    let reward_amount = _settle_and_pay_reward(
+        &mut accts.pool,
+        &mut accts.staked_info,
+        accts.user.key(),
+        &accts.global_state,
+        &accts.ant_food_token,
+        Some(&accts.ant_food_token_vault_account),
+        accts.user_ant_food_token_account.to_account_info(),
+        accts.token_program.to_account_info(),
+        ctx.remaining_accounts
     )?;
-    accts.staked_info.staked_amount = 0;
-    accts.staked_info.reward_debt = 0;
 
-    emit!(FoodGatheringUnStaked {
+    // restart the accrual, the staked amount and the lock stay as they are
+    let staked_amount = accts.staked_info.staked_amount;
+    _update_staked_amount(&mut accts.pool, &mut accts.staked_info, staked_amount);
+
+    emit!(FoodGatheringRewardClaimed {
+        pool: accts.pool.key(),
+        staker: accts.user.key(),
+        reward_ant_food_amount: reward_amount
+    });
+
+    Ok(())
+}
+
+pub fn claim_owed_reward(ctx: Context<ClaimOwedReward>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(accts.staked_info.owed_reward > 0, FoodGatheringError::NothingOwed);
+    let available_reward = _get_available_reward(&accts.pool, accts.ant_food_token_vault_account.amount);
+    let reward_amount = accts.staked_info.owed_reward.min(available_reward);
+    require!(reward_amount > 0, FoodGatheringError::InsufficientRewardBalance);
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let signer = &[&vault_seeds[..]];
+
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        TransferChecked {
+            from: accts.ant_food_token_vault_account.to_account_info().clone(),
+            mint: accts.ant_food_token.to_account_info().clone(),
+            to: accts.user_ant_food_token_account.to_account_info().clone(),
+            authority: accts.global_state.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), reward_amount, accts.ant_food_token.decimals)?;
+
+    accts.staked_info.owed_reward -= reward_amount;
+    accts.pool.total_reward_owed = accts.pool.total_reward_owed.saturating_sub(reward_amount as u128 * accts.pool.precision as u128);
+
+    emit!(FoodGatheringRewardClaimed {
+        pool: accts.pool.key(),
+        staker: accts.user.key(),
+        reward_ant_food_amount: reward_amount
+    });
+
+    Ok(())
+}
+
+pub fn compound(ctx: Context<Compound>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(
+        accts.pool.is_reward_stake_mint && accts.pool.reward_mint == accts.pool.stake_mint,
+        FoodGatheringError::RewardMintMismatch
+    );
+    require!(accts.staked_info.staked_amount > 0, FoodGatheringError::NotStaked);
+
+    _update_pool(&mut accts.pool)?;
+    _settle_stream_rewards(&accts.pool, &mut accts.staked_info);
+
+    // only reward the vault holds above the principal is compounded, so the stake stays backed by tokens
+    let available_reward = _get_available_reward(&accts.pool, accts.ant_coin_vault_account.amount);
+    let (reward_amount, shortfall) = _settle_reward(&mut accts.pool, &mut accts.staked_info, available_reward)?;
+
+    require!(accts.user_positions.staked_amount + reward_amount <= accts.pool.per_user_cap, FoodGatheringError::UserStakingCapAttained);
+    require!(accts.pool.total_staked + reward_amount <= accts.pool.pool_cap, FoodGatheringError::MaxStakingAmountAttained);
+
+    // reward and stake share the same vault, so the reward only has to be credited to the stake
+    // and no burn fee is charged, the lock of the position isn't restarted
+    let staked_amount = accts.staked_info.staked_amount + reward_amount;
+    _update_staked_amount(&mut accts.pool, &mut accts.staked_info, staked_amount);
+    accts.user_positions.staked_amount += reward_amount;
+
+    emit!(FoodGatheringCompounded {
+        pool: accts.pool.key(),
         staker: accts.user.key(),
+        antc_compound_amount: reward_amount,
         antc_stake_amount: accts.staked_info.staked_amount,
-        reward_ant_food_amount: pending_reward.checked_div(accts.global_state.precision as u64).unwrap()
+        pool_total_staked: accts.pool.total_staked,
+        pool_cap: accts.pool.pool_cap
+    });
+
+    if shortfall > 0 {
+        emit!(FoodGatheringRewardShortfall {
+            pool: accts.pool.key(),
+            staker: accts.user.key(),
+            shortfall,
+            owed_reward: accts.staked_info.owed_reward
+        });
+    }
+
+    Ok(())
+}
+
+pub fn close_staked_info(ctx: Context<CloseStakedInfo>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    // only the reward below the precision, which can't be paid out, is dropped with the account
+    accts.pool.total_reward_owed = accts.pool.total_reward_owed.saturating_sub(accts.staked_info.unclaimed_reward as u128);
+    _forfeit_stream_rewards(&mut accts.pool, &mut accts.staked_info);
+
+    Ok(())
+}
+
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    _update_pool(&mut accts.pool)?;
+    _settle_stream_rewards(&accts.pool, &mut accts.staked_info);
+
+    // the lock and the penalty are waived, the pending reward is given up,
+    // the IOU was already earned and stays claimable with claim_owed_reward
+    let antc_amount = accts.staked_info.staked_amount;
+    let forfeited_reward = _get_pending_reward(&accts.pool, &accts.staked_info).unwrap();
+
+    if antc_amount > 0 {
+        let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+        let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+        let signer = &[&vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new(
+            accts.token_program.to_account_info(),
+            TransferChecked {
+                from: accts.ant_coin_vault_account.to_account_info(),
+                mint: accts.ant_coin.to_account_info(),
+                to: accts.user_ant_coin_account.to_account_info(),
+                authority: accts.global_state.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), antc_amount, accts.ant_coin.decimals)?;
+    }
+
+    accts.staked_info.unclaimed_reward = 0;
+    _update_staked_amount(&mut accts.pool, &mut accts.staked_info, 0);
+    accts.user_positions.staked_amount -= antc_amount;
+    accts.pool.total_reward_owed = accts.pool.total_reward_owed.saturating_sub(forfeited_reward as u128);
+    _forfeit_stream_rewards(&mut accts.pool, &mut accts.staked_info);
+
+    emit!(FoodGatheringEmergencyWithdrawn {
+        pool: accts.pool.key(),
+        staker: accts.user.key(),
+        antc_stake_amount: antc_amount,
+        forfeited_reward,
+        pool_total_staked: accts.pool.total_staked,
+        pool_cap: accts.pool.pool_cap
     });
 
+    // a position with an IOU stays open until it's paid out and closed with close_staked_info
+    if accts.staked_info.owed_reward == 0 {
+        accts.staked_info.close(accts.user.to_account_info())?;
+    }
+
     Ok(())
 }
-// minter functions
+
+// role functions
 pub fn deposit_ant_food_token(ctx: Context<DepositAntFoodToken>, amount: u64) -> Result<()> {
     let accts = ctx.accounts;
-    
+
+    _update_pool(&mut accts.pool)?;
+
+    // the deposit funds a new reward period, what's left of the current one rolls into it
+    let now = Clock::get()?.unix_timestamp;
+    let remaining_reward = _get_remaining_reward(accts.pool.reward_rate, accts.pool.period_finish, now);
+    let received_amount = amount - _get_transfer_fee(&accts.ant_food_token.to_account_info(), amount)?;
+    let reward_rate = (received_amount as u128)
+        .checked_mul(accts.pool.precision as u128).unwrap()
+        .checked_add(remaining_reward).unwrap()
+        .checked_div(accts.pool.reward_duration as u128).unwrap();
+    accts.pool.reward_rate = u64::try_from(reward_rate).unwrap();
+    accts.pool.period_finish = now + accts.pool.reward_duration;
+    require!(accts.pool.reward_rate > 0, FoodGatheringError::RewardRateTooLow);
+
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
-            from: accts.minter_ant_food_token_account.to_account_info(),
+        TransferChecked {
+            from: accts.depositor_ant_food_token_account.to_account_info(),
+            mint: accts.ant_food_token.to_account_info(),
             to: accts.ant_food_token_vault_account.to_account_info(),
-            authority: accts.minter_key.to_account_info(),
+            authority: accts.authority.to_account_info(),
+        },
+    );
+
+    transfer_checked(cpi_ctx, amount, accts.ant_food_token.decimals)?;
+
+    emit!(FoodGatheringRewardPeriodFunded {
+        pool: accts.pool.key(),
+        amount: received_amount,
+        reward_rate: accts.pool.reward_rate,
+        period_finish: accts.pool.period_finish
+    });
+
+    Ok(())
+}
+
+pub fn deposit_reward_stream(ctx: Context<DepositRewardStream>, stream_index: u8, amount: u64) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!((stream_index as usize) < MAX_REWARD_STREAMS, FoodGatheringError::InvalidRewardStream);
+    let stream = accts.pool.reward_streams[stream_index as usize];
+    require!(stream.is_active && stream.mint == accts.reward_mint.key(), FoodGatheringError::InvalidRewardStream);
+
+    _update_pool(&mut accts.pool)?;
+
+    // same as for ant food, the deposit funds a new reward period of the stream
+    // and what's left of the current one rolls into it
+    let now = Clock::get()?.unix_timestamp;
+    let reward_duration = accts.pool.reward_duration;
+    let remaining_reward = _get_remaining_reward(stream.reward_rate, stream.period_finish, now);
+    let received_amount = amount - _get_transfer_fee(&accts.reward_mint.to_account_info(), amount)?;
+    let reward_rate = (received_amount as u128)
+        .checked_mul(accts.pool.precision as u128).unwrap()
+        .checked_add(remaining_reward).unwrap()
+        .checked_div(reward_duration as u128).unwrap();
+    let stream = &mut accts.pool.reward_streams[stream_index as usize];
+    stream.reward_rate = u64::try_from(reward_rate).unwrap();
+    stream.period_finish = now + reward_duration;
+    require!(stream.reward_rate > 0, FoodGatheringError::RewardRateTooLow);
+    let (reward_rate, period_finish) = (stream.reward_rate, stream.period_finish);
+
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        TransferChecked {
+            from: accts.depositor_reward_token_account.to_account_info(),
+            mint: accts.reward_mint.to_account_info(),
+            to: accts.reward_vault_account.to_account_info(),
+            authority: accts.authority.to_account_info(),
         },
     );
 
-    transfer(cpi_ctx, amount)?;
+    transfer_checked(cpi_ctx, amount, accts.reward_mint.decimals)?;
+
+    emit!(FoodGatheringRewardStreamFunded {
+        pool: accts.pool.key(),
+        stream_index,
+        amount: received_amount,
+        reward_rate,
+        period_finish
+    });
 
     Ok(())
 }
 
 pub fn set_stake_fee_amount(ctx: Context<SetStakeFeeAmount>, stake_fee_amount: u64) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.stake_fee_amount = stake_fee_amount;
+    accts.pool.stake_fee_amount = stake_fee_amount;
+
+    Ok(())
+}
+
+pub fn set_antc_price(ctx: Context<SetAntcPrice>, antc_price: u64, antc_expo: u64) -> Result<()> {
+    let accts = ctx.accounts;
+    accts.pool.antc_price = antc_price;
+    accts.pool.antc_expo = antc_expo;
+
+    Ok(())
+}
+
+pub fn set_price_feed(ctx: Context<SetPriceFeed>, price_feed: Pubkey, max_price_age: i64, max_price_conf_bps: u32) -> Result<()> {
+    let accts = ctx.accounts;
+    accts.pool.price_feed = price_feed;
+    accts.pool.max_price_age = max_price_age;
+    accts.pool.max_price_conf_bps = max_price_conf_bps;
+
+    Ok(())
+}
+
+pub fn set_reward_duration(ctx: Context<SetRewardDuration>, reward_duration: i64) -> Result<()> {
+    let accts = ctx.accounts;
+    // the duration of a running period can't change, the next deposit uses the new one
+    require!(Clock::get()?.unix_timestamp >= accts.pool.period_finish, FoodGatheringError::RewardPeriodActive);
+    require!(reward_duration > 0, FoodGatheringError::InvalidRewardDuration);
+    accts.pool.reward_duration = reward_duration;
+
+    Ok(())
+}
+
+pub fn set_unstake_cooldown(ctx: Context<SetUnstakeCooldown>, unstake_cooldown: i64) -> Result<()> {
+    let accts = ctx.accounts;
+    require!(unstake_cooldown >= 0, FoodGatheringError::InvalidCooldown);
+    accts.pool.unstake_cooldown = unstake_cooldown;
 
     Ok(())
 }
 
-pub fn set_max_amount_for_stake(ctx: Context<SetMaxAmountForStake>, max_amount_for_stake: u64) -> Result<()> {
+pub fn queue_change(ctx: Context<QueueChange>, kind: u8, new_value: u64) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.max_amount_for_stake = max_amount_for_stake;
+
+    require!(accts.role.has_role(_get_change_role(kind)?), FoodGatheringError::NotAllowedRole);
+    let new_key = match kind {
+        CHANGE_ANT_COIN | CHANGE_ANT_FOOD_TOKEN => accts.new_mint.as_ref().ok_or(FoodGatheringError::MissingMint)?.key(),
+        _ => Pubkey::default(),
+    };
+    require!(kind != CHANGE_TIMELOCK_DELAY || new_value <= i64::MAX as u64, FoodGatheringError::InvalidChange);
+
+    let eta = Clock::get()?.unix_timestamp + accts.pool.timelock_delay;
+    accts.pending_change.pool = accts.pool.key();
+    accts.pending_change.kind = kind;
+    accts.pending_change.new_key = new_key;
+    accts.pending_change.new_value = new_value;
+    accts.pending_change.eta = eta;
+
+    emit!(FoodGatheringChangeQueued {
+        pool: accts.pool.key(),
+        kind,
+        new_key,
+        new_value,
+        eta
+    });
+
+    Ok(())
+}
+
+pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+    let accts = ctx.accounts;
+    let kind = accts.pending_change.kind;
+
+    require!(accts.role.has_role(_get_change_role(kind)?), FoodGatheringError::NotAllowedRole);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= accts.pending_change.eta, FoodGatheringError::ChangeNotReady);
+
+    // a mint is only swapped on an empty pool after its reward period, otherwise principal
+    // and reward would be left behind in the vault of the old mint
+    if kind == CHANGE_ANT_COIN || kind == CHANGE_ANT_FOOD_TOKEN {
+        _update_pool(&mut accts.pool)?;
+        require!(
+            accts.pool.total_staked == 0
+                && accts.pool.total_unbonding == 0
+                && accts.pool.total_reward_owed < accts.pool.precision as u128
+                && now >= accts.pool.period_finish,
+            FoodGatheringError::PoolNotEmpty
+        );
+        // the vault of a stream, retired or not, only backs that stream
+        let new_key = accts.pending_change.new_key;
+        require!(
+            accts.pool.reward_streams.iter().all(|stream| stream.mint != new_key),
+            FoodGatheringError::InvalidChange
+        );
+    }
+
+    let new_key = accts.pending_change.new_key;
+    let new_value = accts.pending_change.new_value;
+    match kind {
+        CHANGE_ANT_COIN => accts.pool.stake_mint = new_key,
+        CHANGE_ANT_FOOD_TOKEN => accts.pool.reward_mint = new_key,
+        CHANGE_PER_USER_CAP => accts.pool.per_user_cap = new_value,
+        CHANGE_POOL_CAP => accts.pool.pool_cap = new_value,
+        CHANGE_TIMELOCK_DELAY => accts.pool.timelock_delay = new_value as i64,
+        _ => return err!(FoodGatheringError::InvalidChange),
+    }
+    accts.pool.is_reward_stake_mint = accts.pool.reward_mint == accts.pool.stake_mint;
+
+    emit!(FoodGatheringChangeExecuted {
+        pool: accts.pool.key(),
+        kind,
+        new_key,
+        new_value
+    });
 
     Ok(())
 }
 
-pub fn set_cycle_staked_amount(ctx: Context<SetCycleStakedAmount>, cycle_staked_amount: u64) -> Result<()> {
+pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.cycle_staked_amount = cycle_staked_amount;
+    let kind = accts.pending_change.kind;
+
+    require!(accts.role.has_role(_get_change_role(kind)?), FoodGatheringError::NotAllowedRole);
+
+    emit!(FoodGatheringChangeCancelled {
+        pool: accts.pool.key(),
+        kind
+    });
 
     Ok(())
 }
 
-pub fn set_cycle_timestamp(ctx: Context<SetCycleTimestamp>, cycle_timestamp: u32) -> Result<()> {
+pub fn set_paused(ctx: Context<SetPaused>, paused_flags: u8) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.cycle_timestamp = cycle_timestamp;
+
+    require!(paused_flags & !PAUSE_ALL == 0, FoodGatheringError::InvalidPauseFlags);
+    let old_paused_flags = accts.global_state.paused_flags;
+    accts.global_state.paused_flags = paused_flags;
+
+    emit!(FoodGatheringPauseStateChanged {
+        old_paused_flags,
+        paused_flags
+    });
 
     Ok(())
 }
 
-pub fn set_ant_food_token(ctx: Context<SetAntFoodToken>) -> Result<()> {
+pub fn set_lock_tier(
+    ctx: Context<SetLockTier>,
+    lock_tier: u8,
+    lock_duration: i64,
+    multiplier_bps: u32,
+    early_unlock_penalty_bps: u32,
+    is_enabled: bool
+) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.ant_food_token = accts.new_ant_food_token.key();
+
+    require!((lock_tier as usize) < MAX_LOCK_TIERS, FoodGatheringError::InvalidLockTier);
+    require!(lock_duration >= 0, FoodGatheringError::InvalidLockTier);
+    // a tier can boost rewards but never earn less than 1x, a zero weight would earn nothing
+    require!(multiplier_bps as u64 >= BPS_DENOMINATOR, FoodGatheringError::InvalidLockTier);
+    require!(early_unlock_penalty_bps as u64 <= BPS_DENOMINATOR, FoodGatheringError::InvalidLockTier);
+
+    // open positions keep the terms they were staked with
+    accts.pool.lock_tiers[lock_tier as usize] = LockTier {
+        lock_duration,
+        multiplier_bps,
+        early_unlock_penalty_bps,
+        is_enabled,
+    };
+
+    emit!(FoodGatheringLockTierSet {
+        pool: accts.pool.key(),
+        lock_tier,
+        lock_duration,
+        multiplier_bps,
+        early_unlock_penalty_bps,
+        is_enabled
+    });
 
     Ok(())
 }
 
-pub fn set_ant_coin(ctx: Context<SetAntCoin>) -> Result<()> {
+pub fn set_penalty_to_treasury(ctx: Context<SetPenaltyToTreasury>, penalty_to_treasury: bool) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.ant_coin = accts.ant_coin.key();
+    accts.global_state.penalty_to_treasury = penalty_to_treasury;
 
     Ok(())
 }
 
 // owner functions
-pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+pub fn add_reward_stream(ctx: Context<AddRewardStream>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    // a stream gets a vault of its own, it can't share one with the stake, ant food or another stream
+    let mint = accts.reward_mint.key();
+    require!(
+        mint != accts.pool.stake_mint
+            && mint != accts.pool.reward_mint
+            && accts.pool.reward_streams.iter().all(|stream| stream.mint != mint),
+        FoodGatheringError::InvalidRewardStream
+    );
+    let stream_index = accts.pool.reward_streams
+        .iter()
+        .position(|stream| stream.mint == Pubkey::default())
+        .ok_or(FoodGatheringError::TooManyRewardStreams)?;
+
+    // the stream starts without a reward period, deposit_reward_stream funds it
+    accts.pool.reward_streams[stream_index] = RewardStream {
+        mint,
+        is_active: true,
+        ..Default::default()
+    };
+
+    emit!(FoodGatheringRewardStreamAdded {
+        pool: accts.pool.key(),
+        stream_index: stream_index as u8,
+        mint
+    });
+
+    Ok(())
+}
+
+pub fn retire_reward_stream(ctx: Context<RetireRewardStream>, stream_index: u8) -> Result<()> {
     let accts = ctx.accounts;
-    accts.global_state.is_paused = paused;
+
+    require!((stream_index as usize) < MAX_REWARD_STREAMS, FoodGatheringError::InvalidRewardStream);
+    require!(accts.pool.reward_streams[stream_index as usize].is_active, FoodGatheringError::InvalidRewardStream);
+
+    _update_pool(&mut accts.pool)?;
+
+    // emission stops now, what stakers earned stays claimable and the unspent budget becomes surplus
+    let now = Clock::get()?.unix_timestamp;
+    let stream = &mut accts.pool.reward_streams[stream_index as usize];
+    stream.is_active = false;
+    stream.reward_rate = 0;
+    stream.period_finish = stream.period_finish.min(now);
+
+    emit!(FoodGatheringRewardStreamRetired {
+        pool: accts.pool.key(),
+        stream_index
+    });
+
+    Ok(())
+}
+
+pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+    let accts = ctx.accounts;
+    accts.global_state.pending_owner = new_owner;
+
+    emit!(FoodGatheringOwnershipProposed {
+        owner: accts.owner.key(),
+        pending_owner: new_owner
+    });
+
+    Ok(())
+}
+
+pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+    let accts = ctx.accounts;
+    let previous_owner = accts.global_state.owner;
+    accts.global_state.owner = accts.pending_owner.key();
+    accts.global_state.pending_owner = Pubkey::default();
+
+    // the roles move with the ownership, so the old key can't keep acting after a rotation
+    accts.role.authority = accts.pending_owner.key();
+    accts.role.roles |= accts.previous_owner_role.roles;
+    accts.previous_owner_role.roles = 0;
+
+    emit!(FoodGatheringOwnershipTransferred {
+        previous_owner,
+        owner: accts.global_state.owner
+    });
+
+    Ok(())
+}
+
+pub fn grant_role(ctx: Context<GrantRole>, roles: u8) -> Result<()> {
+    let accts = ctx.accounts;
+    require!(roles & !ROLE_ALL == 0, FoodGatheringError::InvalidRoles);
+    accts.role.authority = accts.authority.key();
+    accts.role.roles |= roles;
+
+    emit!(FoodGatheringRoleGranted {
+        authority: accts.authority.key(),
+        granted_roles: roles,
+        roles: accts.role.roles
+    });
 
     Ok(())
 }
 
-pub fn set_minter_role(ctx: Context<SetMinterRole>, is_minter: bool) -> Result<()> {
+pub fn revoke_role(ctx: Context<RevokeRole>, roles: u8) -> Result<()> {
     let accts = ctx.accounts;
-    accts.minter.minter_key = accts.minter_key.key();
-    accts.minter.is_minter = is_minter;
+    accts.role.roles &= !roles;
+
+    emit!(FoodGatheringRoleRevoked {
+        authority: accts.authority.key(),
+        revoked_roles: roles,
+        roles: accts.role.roles
+    });
 
     Ok(())
 }
@@ -247,296 +943,1323 @@ pub fn withdraw_sol(ctx: Context<WithdrawSOL>, amount: u64) -> Result<()> {
 pub fn withdraw_token(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
     let accts = ctx.accounts;
 
-    let binding = accts.owner.key();
-    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED, binding.as_ref()], ctx.program_id);
-    let vault_seeds = &[GLOBAL_STATE_SEED, binding.as_ref(), &[bump]];
+    // only the surplus above what's owed to stakers can leave the vault
+    _update_pool(&mut accts.pool)?;
+    let token_mint = accts.token_mint.key();
+    let mut liabilities = 0;
+    if token_mint == accts.pool.stake_mint {
+        liabilities += accts.pool.total_staked + accts.pool.total_unbonding;
+    }
+    if token_mint == accts.pool.reward_mint {
+        liabilities += _get_reward_liability(&accts.pool, Clock::get()?.unix_timestamp);
+    }
+    for stream in accts.pool.reward_streams.iter() {
+        if token_mint == stream.mint {
+            liabilities += _get_stream_reward_liability(&accts.pool, stream, Clock::get()?.unix_timestamp);
+        }
+    }
+    let surplus = accts.token_vault_account.amount.saturating_sub(liabilities);
+    require!(amount <= surplus, FoodGatheringError::InsufficientSurplus);
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
     let signer = &[&vault_seeds[..]];
 
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
+        TransferChecked {
             from: accts.token_vault_account.to_account_info().clone(),
+            mint: accts.token_mint.to_account_info().clone(),
             to: accts.token_owner_account.to_account_info().clone(),
             authority: accts.global_state.to_account_info().clone(),
         },
     );
-    transfer(cpi_ctx.with_signer(signer), amount)?;
+    transfer_checked(cpi_ctx.with_signer(signer), amount, accts.token_mint.decimals)?;
+
+    emit!(FoodGatheringTreasuryWithdrawal {
+        pool: accts.pool.key(),
+        mint: token_mint,
+        to: accts.token_owner_account.key(),
+        amount,
+        remaining_surplus: surplus - amount
+    });
 
     Ok(())
 }
 
-pub fn get_pending_reward(ctx: Context<GetPendingReward>) -> Result<u64> {
+pub fn init_treasury(_ctx: Context<InitTreasury>) -> Result<()> {
+    Ok(())
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let signer = &[&vault_seeds[..]];
+
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        TransferChecked {
+            from: accts.treasury_account.to_account_info().clone(),
+            mint: accts.token_mint.to_account_info().clone(),
+            to: accts.token_owner_account.to_account_info().clone(),
+            authority: accts.global_state.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), amount, accts.token_mint.decimals)?;
+
+    Ok(())
+}
+
+// multisig functions
+pub fn create_multisig(ctx: Context<CreateMultisig>, multisig_id: u64, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let accts = ctx.accounts;
+
+    _validate_multisig_signers(&signers, threshold)?;
+    accts.multisig.multisig_id = multisig_id;
+    accts.multisig.set_signers(&signers, threshold);
+
+    let (multisig_signer, _) = Pubkey::find_program_address(&[MULTISIG_SIGNER_SEED, accts.multisig.key().as_ref()], ctx.program_id);
+    emit!(FoodGatheringMultisigCreated {
+        multisig: accts.multisig.key(),
+        multisig_signer,
+        signers,
+        threshold
+    });
+
+    Ok(())
+}
+
+pub fn set_multisig_signers(ctx: Context<SetMultisigSigners>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let accts = ctx.accounts;
+
+    // approvals given to the old signer set are dropped with it
+    _validate_multisig_signers(&signers, threshold)?;
+    accts.multisig.set_signers(&signers, threshold);
+
+    emit!(FoodGatheringMultisigSignersChanged {
+        multisig: accts.multisig.key(),
+        signers,
+        threshold
+    });
+
+    Ok(())
+}
+
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    target_program_id: Pubkey,
+    target_accounts: Vec<ProposalAccount>,
+    target_data: Vec<u8>
+) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let signer_index = accts.multisig.signer_index(&accts.proposer.key()).ok_or(FoodGatheringError::NotMultisigSigner)?;
+    let proposal_id = accts.multisig.proposal_count;
+    accts.multisig.proposal_count += 1;
+
+    accts.proposal.multisig = accts.multisig.key();
+    accts.proposal.proposal_id = proposal_id;
+    accts.proposal.proposer = accts.proposer.key();
+    accts.proposal.target_program_id = target_program_id;
+    accts.proposal.target_accounts = target_accounts;
+    accts.proposal.target_data = target_data;
+    accts.proposal.approvals = 1 << signer_index;
+    accts.proposal.signers_seqno = accts.multisig.signers_seqno;
+    accts.proposal.is_executed = false;
+
+    emit!(FoodGatheringProposalCreated {
+        multisig: accts.multisig.key(),
+        proposal: accts.proposal.key(),
+        proposal_id,
+        proposer: accts.proposer.key()
+    });
+
+    Ok(())
+}
+
+pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let signer_index = accts.multisig.signer_index(&accts.approver.key()).ok_or(FoodGatheringError::NotMultisigSigner)?;
+    require!(accts.proposal.signers_seqno == accts.multisig.signers_seqno, FoodGatheringError::StaleProposal);
+    require!(!accts.proposal.is_executed, FoodGatheringError::ProposalAlreadyExecuted);
+    accts.proposal.approvals |= 1 << signer_index;
+
+    emit!(FoodGatheringProposalApproved {
+        multisig: accts.multisig.key(),
+        proposal: accts.proposal.key(),
+        approver: accts.approver.key(),
+        approval_count: accts.proposal.approvals.count_ones() as u8
+    });
+
+    Ok(())
+}
+
+pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
     let accts = ctx.accounts;
-    _get_pending_reward(&accts.global_state, &accts.staked_info)
+
+    require!(accts.proposal.signers_seqno == accts.multisig.signers_seqno, FoodGatheringError::StaleProposal);
+    require!(!accts.proposal.is_executed, FoodGatheringError::ProposalAlreadyExecuted);
+    require!(
+        accts.proposal.approvals.count_ones() >= accts.multisig.threshold as u32,
+        FoodGatheringError::NotEnoughApprovals
+    );
+
+    // persist the flag before the call so the proposal can't be replayed from inside it
+    accts.proposal.is_executed = true;
+    accts.proposal.exit(ctx.program_id)?;
+
+    let ix = Instruction {
+        program_id: accts.proposal.target_program_id,
+        accounts: accts.proposal.target_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: accts.proposal.target_data.clone(),
+    };
+
+    let multisig_key = accts.multisig.key();
+    let (_, bump) = Pubkey::find_program_address(&[MULTISIG_SIGNER_SEED, multisig_key.as_ref()], ctx.program_id);
+    let signer_seeds = &[MULTISIG_SIGNER_SEED, multisig_key.as_ref(), &[bump]];
+    invoke_signed(&ix, ctx.remaining_accounts, &[&signer_seeds[..]])?;
+
+    emit!(FoodGatheringProposalExecuted {
+        multisig: multisig_key,
+        proposal: accts.proposal.key(),
+        proposal_id: accts.proposal.proposal_id
+    });
+
+    Ok(())
+}
+
+pub fn get_pending_reward(ctx: Context<GetPendingReward>) -> Result<PendingRewardInfo> {
+    let accts = ctx.accounts;
+    let now = Clock::get()?.unix_timestamp;
+
+    Ok(PendingRewardInfo {
+        pending_reward: _get_pending_reward(&accts.pool, &accts.staked_info)?,
+        stream_pending_rewards: _get_stream_pending_rewards(&accts.pool, &accts.staked_info, now),
+        early_unlock_penalty: _get_early_unlock_penalty(&accts.staked_info, accts.staked_info.staked_amount, now),
+        unlock_timestamp: accts.staked_info.staked_timestamp + accts.staked_info.lock_duration,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(new_owner: Pubkey)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    // a singleton, so the program can only be initialized once
+    #[account(
+        init,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        space = 8 + size_of::<GlobalState>(),
+        payer = owner,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED],
+        bump
+    )]
+    /// CHECK: this should be set by admin
+    pub vault: AccountInfo<'info>,  // to receive SOL
+
+    #[account(
+        init,
+        seeds = [ROLE_STATE_SEED, new_owner.key().as_ref()],
+        bump,
+        space = 8 + size_of::<Role>(),
+        payer = owner,
+    )]
+    pub role: Account<'info, Role>,
+
+    // only the upgrade authority of the program can initialize it, so nobody can front-run the deployment
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::FoodGathering>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(owner.key()) @ FoodGatheringError::NotAllowedOwner)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = owner,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + size_of::<Pool>(),
+        payer = owner,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// TODO Don't forget that the data account size can't be adjusted, so make sure you allocate it as much as you need.
+// #[account]
+#[derive(Accounts)]
+#[instruction(antc_amount: u64, lock_tier: u8, position_id: u64)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_STAKE),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [USER_POSITIONS_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + size_of::<UserPositions>(),
+    )]
+    pub user_positions: Box<Account<'info, UserPositions>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + size_of::<StakedInfo>(),
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        address = pool.stake_mint
+    )]
+    pub ant_coin: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    ant_coin_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // user account for ant coin
+    #[account(mut)]
+    pub user_ant_coin_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // required when the pool has a price feed
+    #[account(address = pool.price_feed)]
+    /// CHECK: this should be checked with address in pool
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_UNSTAKE),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        seeds = [USER_POSITIONS_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_positions: Box<Account<'info, UserPositions>>,
+
+    #[account(
+        mut,
+        address = pool.stake_mint,
+        mint::token_program = stake_token_program,
+    )]
+    pub ant_coin: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    pub ant_coin_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // user account for ant coin
+    #[account(mut)]
+    user_ant_coin_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // only needed when an early unlock penalty is sent to the treasury
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    pub treasury_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        address = pool.reward_mint,
+        mint::token_program = reward_token_program,
+    )]
+    pub ant_food_token: Box<InterfaceAccount<'info, Mint>>,
+
+    // created by the first reward deposit, without it the whole reward is kept as an IOU
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_food_token.key().as_ref()],
+        bump,
+        token::mint = ant_food_token,
+        token::authority = global_state,
+    )]
+    pub ant_food_token_vault_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // user account for ant food coin
+    #[account(mut)]
+    pub user_ant_food_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // stake and reward mints can live on different token programs
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(new_owner: Pubkey)]
-pub struct Initialize<'info> {
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_UNSTAKE),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        seeds = [USER_POSITIONS_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_positions: Box<Account<'info, UserPositions>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [UNSTAKE_REQUEST_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + size_of::<UnstakeRequests>(),
+    )]
+    pub unstake_requests: Box<Account<'info, UnstakeRequests>>,
+
+    #[account(
+        mut,
+        address = pool.stake_mint
+    )]
+    pub ant_coin: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    pub ant_coin_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // only needed when an early unlock penalty is sent to the treasury
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    pub treasury_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [UNSTAKE_REQUEST_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub unstake_requests: Box<Account<'info, UnstakeRequests>>,
+
+    #[account(
+        mut,
+        address = pool.stake_mint
+    )]
+    pub ant_coin: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    pub ant_coin_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // user account for ant coin
+    #[account(mut)]
+    pub user_ant_coin_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_CLAIM),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        address = pool.reward_mint
+    )]
+    pub ant_food_token: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_food_token.key().as_ref()],
+        bump,
+        token::mint = ant_food_token,
+        token::authority = global_state,
+    )]
+    pub ant_food_token_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // user account for ant food coin
+    #[account(mut)]
+    pub user_ant_food_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimOwedReward<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_CLAIM),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        address = pool.reward_mint
+    )]
+    pub ant_food_token: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_food_token.key().as_ref()],
+        bump,
+        token::mint = ant_food_token,
+        token::authority = global_state,
+    )]
+    pub ant_food_token_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // user account for ant food coin
+    #[account(mut)]
+    pub user_ant_food_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Compound<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_STAKE | PAUSE_CLAIM),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        seeds = [USER_POSITIONS_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_positions: Box<Account<'info, UserPositions>>,
+
+    // holds both the stake and the reward of the pool
+    #[account(
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        token::authority = global_state,
+    )]
+    pub ant_coin_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStakedInfo<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = staked_info.staked_amount == 0 @ FoodGatheringError::PositionNotEmpty,
+        constraint = staked_info.unclaimed_reward < pool.precision as u64 @ FoodGatheringError::PositionNotEmpty,
+        constraint = staked_info.owed_reward == 0 @ FoodGatheringError::PositionNotEmpty,
+        constraint = !_has_stream_rewards(&pool, &staked_info) @ FoodGatheringError::PositionNotEmpty,
+        close = user,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // only available while unstaking is paused
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = global_state.is_paused(PAUSE_UNSTAKE),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), user.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub staked_info: Account<'info, StakedInfo>,
+
+    #[account(
+        mut,
+        seeds = [USER_POSITIONS_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_positions: Box<Account<'info, UserPositions>>,
+
+    #[account(
+        mut,
+        address = pool.stake_mint
+    )]
+    pub ant_coin: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_coin.key().as_ref()],
+        bump,
+        token::mint = ant_coin,
+        token::authority = global_state,
+    )]
+    pub ant_coin_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // user account for ant coin
+    #[account(
+        mut,
+        token::mint = ant_coin,
+    )]
+    user_ant_coin_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositAntFoodToken<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_DEPOSIT),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_REWARD_DEPOSITOR) @ FoodGatheringError::NotAllowedRole,
+    )]
+    pub role: Account<'info, Role>,
+
+    #[account(
+        mut,
+        address = pool.reward_mint
+    )]
+    pub ant_food_token: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), ant_food_token.key().as_ref()],
+        bump,
+        token::mint = ant_food_token,
+        token::authority = global_state,
+    )]
+    pub ant_food_token_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // depositor account for ant food token
+    #[account(mut)]
+    pub depositor_ant_food_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakeFeeAmount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_FEE_MANAGER) @ FoodGatheringError::NotAllowedRole,
+    )]
+    pub role: Account<'info, Role>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetAntcPrice<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_FEE_MANAGER) @ FoodGatheringError::NotAllowedRole,
+    )]
+    pub role: Account<'info, Role>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_FEE_MANAGER) @ FoodGatheringError::NotAllowedRole,
+    )]
+    pub role: Account<'info, Role>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardDuration<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_PARAM_MANAGER) @ FoodGatheringError::NotAllowedRole,
+    )]
+    pub role: Account<'info, Role>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetUnstakeCooldown<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_PARAM_MANAGER) @ FoodGatheringError::NotAllowedRole,
+    )]
+    pub role: Account<'info, Role>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+
+#[derive(Accounts)]
+#[instruction(kind: u8)]
+pub struct QueueChange<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
-        init_if_needed,
-        seeds = [GLOBAL_STATE_SEED, owner.key().as_ref()],
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
         bump,
-        space = 8 + size_of::<GlobalState>(),
-        payer = owner,
+        constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [VAULT_SEED],
-        bump
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
     )]
-    /// CHECK: this should be set by admin
-    pub vault: AccountInfo<'info>,  // to receive SOL
+    pub pool: Box<Account<'info, Pool>>,
 
+    // the role needed depends on the kind of change
     #[account(
-        init_if_needed,
-        seeds = [MINTER_STATE_SEED, new_owner.key().as_ref()],
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
         bump,
-        space = 8 + size_of::<Minter>(),
-        payer = owner,
+        has_one = authority,
+    )]
+    pub role: Account<'info, Role>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PENDING_CHANGE_SEED, pool.key().as_ref(), kind.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + size_of::<PendingChange>(),
     )]
-    pub minter: Account<'info, Minter>,
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    // required when the change sets a mint
+    pub new_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-impl<'info> Initialize<'info> {
-    pub fn validate(&self) -> Result<()> {
-        if self.global_state.is_initialized == 1 {
-            require!(
-                self.global_state.owner.eq(&self.owner.key()),
-                FoodGatheringError::NotAllowedOwner
-            )
-        }
-        Ok(())
-    }
-}
-
-// TODO Don't forget that the data account size can't be adjusted, so make sure you allocate it as much as you need.
-// #[account]
 #[derive(Accounts)]
-pub struct Stake<'info> {
+pub struct ExecuteChange<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         constraint = global_state.is_initialized == 1,
-        constraint = global_state.is_paused == false,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
-        init_if_needed,
-        payer = user,
-        seeds = [STAKED_INFO_SEED, user.key().as_ref()],
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
         bump,
-        space = 8 + size_of::<StakedInfo>(),
+        has_one = global_state,
     )]
-    pub staked_info: Account<'info, StakedInfo>,
+    pub pool: Box<Account<'info, Pool>>,
 
+    // the role needed depends on the kind of change
     #[account(
-        mut,
-        address = global_state.ant_coin
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
     )]
-    pub ant_coin: Box<Account<'info, Mint>>,
+    pub role: Account<'info, Role>,
 
     #[account(
-        init_if_needed,
-        payer = user,
-        seeds = [TOKEN_VAULT_SEED, ant_coin.key().as_ref()],
+        mut,
+        close = authority,
+        seeds = [PENDING_CHANGE_SEED, pool.key().as_ref(), pending_change.kind.to_le_bytes().as_ref()],
         bump,
-        token::mint = ant_coin,
-        token::authority = global_state,
+        constraint = pending_change.pool == pool.key(),
     )]
-    ant_coin_vault_account: Box<Account<'info, TokenAccount>>,
+    pub pending_change: Box<Account<'info, PendingChange>>,
 
-    // user account for ant coin
-    #[account(mut)]
-    pub user_ant_coin_account: Box<Account<'info, TokenAccount>>,
-
-    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Unstake<'info> {
+pub struct CancelChange<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         constraint = global_state.is_initialized == 1,
-        constraint = global_state.is_paused == false,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [STAKED_INFO_SEED, user.key().as_ref()],
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
         bump,
+        has_one = global_state,
     )]
-    pub staked_info: Account<'info, StakedInfo>,
+    pub pool: Box<Account<'info, Pool>>,
 
+    // the role needed depends on the kind of change
     #[account(
-        mut,
-        address = global_state.ant_coin
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
     )]
-    pub ant_coin: Box<Account<'info, Mint>>,
+    pub role: Account<'info, Role>,
 
     #[account(
         mut,
-        seeds = [TOKEN_VAULT_SEED, ant_coin.key().as_ref()],
+        close = authority,
+        seeds = [PENDING_CHANGE_SEED, pool.key().as_ref(), pending_change.kind.to_le_bytes().as_ref()],
         bump,
-        token::mint = ant_coin,
-        token::authority = global_state,
+        constraint = pending_change.pool == pool.key(),
     )]
-    pub ant_coin_vault_account: Box<Account<'info, TokenAccount>>,
+    pub pending_change: Box<Account<'info, PendingChange>>,
 
-    // user account for ant coin
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
     #[account(mut)]
-    user_ant_coin_account: Box<Account<'info, TokenAccount>>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        address = global_state.ant_food_token
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global_state.is_initialized == 1,
     )]
-    pub ant_food_token: Box<Account<'info, Mint>>,
+    pub global_state: Account<'info, GlobalState>,
 
     #[account(
-        mut,
-        seeds = [TOKEN_VAULT_SEED, ant_food_token.key().as_ref()],
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
         bump,
-        token::mint = ant_food_token,
-        token::authority = global_state,
+        has_one = authority,
+        constraint = role.has_role(ROLE_PAUSER) @ FoodGatheringError::NotAllowedRole,
     )]
-    pub ant_food_token_vault_account: Box<Account<'info, TokenAccount>>,
-
-    // user account for ant food coin
-    #[account(mut)]
-    pub user_ant_food_token_account: Box<Account<'info, TokenAccount>>,
+    pub role: Account<'info, Role>,
 
-    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct DepositAntFoodToken<'info> {
+pub struct DepositRewardStream<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_DEPOSIT),
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        has_one = global_state,
     )]
-    pub minter: Account<'info, Minter>,
+    pub pool: Box<Account<'info, Pool>>,
 
     #[account(
-        mut,
-        address = global_state.ant_food_token
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_REWARD_DEPOSITOR) @ FoodGatheringError::NotAllowedRole,
     )]
-    pub ant_food_token: Box<Account<'info, Mint>>,
+    pub role: Account<'info, Role>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
-        init_if_needed,
-        payer = minter_key,
-        seeds = [TOKEN_VAULT_SEED, ant_food_token.key().as_ref()],
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), reward_mint.key().as_ref()],
         bump,
-        token::mint = ant_food_token,
+        token::mint = reward_mint,
         token::authority = global_state,
     )]
-    pub ant_food_token_vault_account: Box<Account<'info, TokenAccount>>,
+    pub reward_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    // minter account for ant food token
+    // depositor account for the stream reward token
     #[account(mut)]
-    pub minter_ant_food_token_account: Account<'info, TokenAccount>,
+    pub depositor_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetStakeFeeAmount<'info> {
+pub struct AddRewardStream<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
+        has_one = owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), reward_mint.key().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        token::mint = reward_mint,
+        token::authority = global_state,
     )]
-    pub minter: Account<'info, Minter>,
+    pub reward_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetMaxAmountForStake<'info> {
+pub struct RetireRewardStream<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
+        has_one = owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        has_one = global_state,
     )]
-    pub minter: Account<'info, Minter>,
+    pub pool: Box<Account<'info, Pool>>,
 
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetCycleStakedAmount<'info> {
+pub struct SetLockTier<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         constraint = global_state.is_initialized == 1,
     )]
@@ -544,242 +2267,442 @@ pub struct SetCycleStakedAmount<'info> {
 
     #[account(
         mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        has_one = authority,
+        constraint = role.has_role(ROLE_PARAM_MANAGER) @ FoodGatheringError::NotAllowedRole,
     )]
-    pub minter: Account<'info, Minter>,
+    pub role: Account<'info, Role>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetCycleTimestamp<'info> {
+pub struct ProposeOwner<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = owner,
+        constraint = global_state.is_initialized == 1,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub pending_owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
+        has_one = pending_owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        seeds = [ROLE_STATE_SEED, global_state.owner.as_ref()],
+        bump,
+    )]
+    pub previous_owner_role: Account<'info, Role>,
+
+    #[account(
+        init_if_needed,
+        seeds = [ROLE_STATE_SEED, pending_owner.key().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        space = 8 + size_of::<Role>(),
+        payer = pending_owner,
     )]
-    pub minter: Account<'info, Minter>,
+    pub role: Account<'info, Role>,
 
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetAntFoodToken<'info> {
+pub struct GrantRole<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
+        has_one = owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    /// CHECK: any key can be given roles
+    pub authority: AccountInfo<'info>,
+
     #[account(
-        mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        init_if_needed,
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        space = 8 + size_of::<Role>(),
+        payer = owner,
     )]
-    pub minter: Account<'info, Minter>,
-
-    pub new_ant_food_token: Box<Account<'info, Mint>>,
+    pub role: Account<'info, Role>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetAntCoin<'info> {
+pub struct RevokeRole<'info> {
     #[account(mut)]
-    pub minter_key: Signer<'info>,
+    pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
+        has_one = owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    /// CHECK: this should be checked with address in role
+    pub authority: AccountInfo<'info>,
+
     #[account(
         mut,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
         bump,
-        has_one = minter_key,
-        constraint = minter.is_minter == true,
+        has_one = authority,
     )]
-    pub minter: Account<'info, Minter>,
-
-    pub ant_coin: Box<Account<'info, Mint>>,
+    pub role: Account<'info, Role>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-
 #[derive(Accounts)]
-pub struct SetPaused<'info> {
+pub struct WithdrawSOL<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         has_one = owner,
         constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_ADMIN_WITHDRAW),
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    #[account(
+        mut,
+        address = global_state.vault
+    )]
+    /// CHECK: this should be checked with address in global_state
+    pub vault: AccountInfo<'info>,
+
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SetMinterRole<'info> {
+pub struct WithdrawToken<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         has_one = owner,
         constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_ADMIN_WITHDRAW),
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    #[account(
+        mut,
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        address = global_state.vault
+    )]
     /// CHECK: this should be checked with address in global_state
-    pub minter_key: AccountInfo<'info>,
+    pub vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
-        init_if_needed,
-        seeds = [MINTER_STATE_SEED, minter_key.key().as_ref()],
+        mut,
+        seeds = [TOKEN_VAULT_SEED, pool.key().as_ref(), token_mint.key().as_ref()],
         bump,
-        space = 8 + size_of::<Minter>(),
+        token::mint = token_mint,
+        token::authority = global_state,
+    )]
+    token_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
         payer = owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
     )]
-    pub minter: Account<'info, Minter>,
+    token_owner_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawSOL<'info> {
+pub struct SetPenaltyToTreasury<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
-        has_one = owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
-        mut,
-        address = global_state.vault
+        seeds = [ROLE_STATE_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority,
+        constraint = role.has_role(ROLE_PARAM_MANAGER) @ FoodGatheringError::NotAllowedRole,
     )]
-    /// CHECK: this should be checked with address in global_state
-    pub vault: AccountInfo<'info>,
+    pub role: Account<'info, Role>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawToken<'info> {
+pub struct InitTreasury<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [GLOBAL_STATE_SEED, owner.key().as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
         has_one = owner,
         constraint = global_state.is_initialized == 1,
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(
-        mut,
-        address = global_state.vault
+        init,
+        payer = owner,
+        seeds = [TREASURY_SEED, token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = global_state,
     )]
-    /// CHECK: this should be checked with address in global_state
-    pub vault: AccountInfo<'info>,
+    pub treasury_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
 
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
     #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = owner,
+        constraint = global_state.is_initialized == 1,
+        constraint = !global_state.is_paused(PAUSE_ADMIN_WITHDRAW),
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
-        seeds = [TOKEN_VAULT_SEED, token_mint.key().as_ref()],
+        seeds = [TREASURY_SEED, token_mint.key().as_ref()],
         bump,
         token::mint = token_mint,
         token::authority = global_state,
     )]
-    token_vault_account: Box<Account<'info, TokenAccount>>,
+    pub treasury_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = owner,
         associated_token::mint = token_mint,
-        associated_token::authority = owner
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
     )]
-    token_owner_account: Box<Account<'info, TokenAccount>>,
+    pub token_owner_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+#[instruction(multisig_id: u64)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [MULTISIG_SEED, multisig_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + size_of::<Multisig>(),
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetMultisigSigners<'info> {
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED, multisig.multisig_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    // only an executed proposal of the multisig can change its signers
+    #[account(
+        seeds = [MULTISIG_SIGNER_SEED, multisig.key().as_ref()],
+        bump,
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(target_program_id: Pubkey, target_accounts: Vec<ProposalAccount>, target_data: Vec<u8>)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED, multisig.multisig_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), multisig.proposal_count.to_le_bytes().as_ref()],
+        bump,
+        space = Proposal::space(target_accounts.len(), target_data.len()),
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [MULTISIG_SEED, multisig.multisig_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = multisig,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+// the accounts of the proposed instruction, and its program, are passed as remaining accounts
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        seeds = [MULTISIG_SEED, multisig.multisig_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = multisig,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
 #[derive(Accounts)]
 pub struct GetPendingReward<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
     #[account(
-        seeds = [GLOBAL_STATE_SEED, global_state.owner.as_ref()],
+        seeds = [GLOBAL_STATE_SEED],
         bump,
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    #[account(
+        seeds = [POOL_SEED, global_state.key().as_ref(), pool.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = global_state,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
     #[account(mut)]
     /// CHECK: this should be checked with address in global_state
     pub staker: AccountInfo<'info>,
 
     #[account(
-        seeds = [STAKED_INFO_SEED, staker.key().as_ref()],
+        seeds = [STAKED_INFO_SEED, pool.key().as_ref(), staker.key().as_ref(), staked_info.position_id.to_le_bytes().as_ref()],
         bump,
     )]
     pub staked_info: Account<'info, StakedInfo>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
\ No newline at end of file
@@ -16,56 +16,143 @@ pub mod food_gathering {
 
     // user function
 
-    pub fn stake(ctx: Context<Stake>, antc_amount: u64) -> Result<()> {
-        instructions::stake(ctx, antc_amount)
+    pub fn stake(ctx: Context<Stake>, antc_amount: u64, lock_tier: u8, position_id: u64) -> Result<()> {
+        instructions::stake(ctx, antc_amount, lock_tier, position_id)
     }
 
-    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
-        instructions::unstake(ctx)
+    pub fn unstake<'info>(ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>, antc_amount: u64, accept_penalty: bool) -> Result<()> {
+        instructions::unstake(ctx, antc_amount, accept_penalty)
     }
 
-    // owner or minter function
+    pub fn request_unstake(ctx: Context<RequestUnstake>, antc_amount: u64, accept_penalty: bool) -> Result<()> {
+        instructions::request_unstake(ctx, antc_amount, accept_penalty)
+    }
+
+    pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+        instructions::withdraw_unstaked(ctx)
+    }
+
+    pub fn claim_reward<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimReward<'info>>) -> Result<()> {
+        instructions::claim_reward(ctx)
+    }
+
+    pub fn claim_owed_reward(ctx: Context<ClaimOwedReward>) -> Result<()> {
+        instructions::claim_owed_reward(ctx)
+    }
+
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
+        instructions::compound(ctx)
+    }
+
+    pub fn close_staked_info(ctx: Context<CloseStakedInfo>) -> Result<()> {
+        instructions::close_staked_info(ctx)
+    }
+
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        instructions::emergency_withdraw(ctx)
+    }
 
-    pub fn initialize(ctx: Context<Initialize>, new_owner: Pubkey, antc_price: u64, antc_expo: u64) -> Result<()> {
-        instructions::initialize(ctx, new_owner, antc_price, antc_expo)
+    // owner or role function
+
+    pub fn initialize(ctx: Context<Initialize>, new_owner: Pubkey) -> Result<()> {
+        instructions::initialize(ctx, new_owner)
+    }
+
+    pub fn create_pool(ctx: Context<CreatePool>, pool_id: u64, antc_price: u64, antc_expo: u64, timelock_delay: i64) -> Result<()> {
+        instructions::create_pool(ctx, pool_id, antc_price, antc_expo, timelock_delay)
     }
 
     pub fn deposit_ant_food_token(ctx: Context<DepositAntFoodToken>, amount: u64) -> Result<()> {
         instructions::deposit_ant_food_token(ctx, amount)
     }
 
+    pub fn deposit_reward_stream(ctx: Context<DepositRewardStream>, stream_index: u8, amount: u64) -> Result<()> {
+        instructions::deposit_reward_stream(ctx, stream_index, amount)
+    }
+
     pub fn set_stake_fee_amount(ctx: Context<SetStakeFeeAmount>, stake_fee_amount: u64) -> Result<()> {
         instructions::set_stake_fee_amount(ctx, stake_fee_amount)
     }
 
-    pub fn set_max_amount_for_stake(ctx: Context<SetMaxAmountForStake>, max_amount_for_stake: u64) -> Result<()> {
-        instructions::set_max_amount_for_stake(ctx, max_amount_for_stake)
+    pub fn set_antc_price(ctx: Context<SetAntcPrice>, antc_price: u64, antc_expo: u64) -> Result<()> {
+        instructions::set_antc_price(ctx, antc_price, antc_expo)
+    }
+
+    pub fn set_price_feed(ctx: Context<SetPriceFeed>, price_feed: Pubkey, max_price_age: i64, max_price_conf_bps: u32) -> Result<()> {
+        instructions::set_price_feed(ctx, price_feed, max_price_age, max_price_conf_bps)
+    }
+
+    pub fn set_reward_duration(ctx: Context<SetRewardDuration>, reward_duration: i64) -> Result<()> {
+        instructions::set_reward_duration(ctx, reward_duration)
+    }
+
+    pub fn set_unstake_cooldown(ctx: Context<SetUnstakeCooldown>, unstake_cooldown: i64) -> Result<()> {
+        instructions::set_unstake_cooldown(ctx, unstake_cooldown)
+    }
+
+    pub fn queue_change(ctx: Context<QueueChange>, kind: u8, new_value: u64) -> Result<()> {
+        instructions::queue_change(ctx, kind, new_value)
     }
 
-    pub fn set_cycle_staked_amount(ctx: Context<SetCycleStakedAmount>, cycle_staked_amount: u64) -> Result<()> {
-        instructions::set_cycle_staked_amount(ctx, cycle_staked_amount)
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        instructions::execute_change(ctx)
     }
 
-    pub fn set_cycle_timestamp(ctx: Context<SetCycleTimestamp>, cycle_timestamp: u32) -> Result<()> {
-        instructions::set_cycle_timestamp(ctx, cycle_timestamp)
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        instructions::cancel_change(ctx)
     }
 
-    pub fn set_ant_food_token(ctx: Context<SetAntFoodToken>) -> Result<()> {
-        instructions::set_ant_food_token(ctx)
+    pub fn set_paused(ctx: Context<SetPaused>, paused_flags: u8) -> Result<()> {
+        instructions::set_paused(ctx, paused_flags)
     }
 
-    pub fn set_ant_coin(ctx: Context<SetAntCoin>) -> Result<()> {
-        instructions::set_ant_coin(ctx)
+    pub fn set_lock_tier(
+        ctx: Context<SetLockTier>,
+        lock_tier: u8,
+        lock_duration: i64,
+        multiplier_bps: u32,
+        early_unlock_penalty_bps: u32,
+        is_enabled: bool
+    ) -> Result<()> {
+        instructions::set_lock_tier(ctx, lock_tier, lock_duration, multiplier_bps, early_unlock_penalty_bps, is_enabled)
+    }
+
+    pub fn set_penalty_to_treasury(ctx: Context<SetPenaltyToTreasury>, penalty_to_treasury: bool) -> Result<()> {
+        instructions::set_penalty_to_treasury(ctx, penalty_to_treasury)
     }
 
     // owner function
 
-    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
-        instructions::set_paused(ctx, paused)
+    pub fn add_reward_stream(ctx: Context<AddRewardStream>) -> Result<()> {
+        instructions::add_reward_stream(ctx)
+    }
+
+    pub fn retire_reward_stream(ctx: Context<RetireRewardStream>, stream_index: u8) -> Result<()> {
+        instructions::retire_reward_stream(ctx, stream_index)
     }
 
-    pub fn set_minter_role(ctx: Context<SetMinterRole>, is_minter: bool) -> Result<()> {
-        instructions::set_minter_role(ctx, is_minter)
+    pub fn init_treasury(ctx: Context<InitTreasury>) -> Result<()> {
+        instructions::init_treasury(ctx)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)
+    }
+
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        instructions::propose_owner(ctx, new_owner)
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        instructions::accept_owner(ctx)
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, roles: u8) -> Result<()> {
+        instructions::grant_role(ctx, roles)
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, roles: u8) -> Result<()> {
+        instructions::revoke_role(ctx, roles)
     }
 
     pub fn withdraw_sol(ctx: Context<WithdrawSOL>, amount: u64) -> Result<()> {
@@ -76,9 +163,36 @@ pub mod food_gathering {
         instructions::withdraw_token(ctx, amount)
     }
 
+    // multisig function
+
+    pub fn create_multisig(ctx: Context<CreateMultisig>, multisig_id: u64, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::create_multisig(ctx, multisig_id, signers, threshold)
+    }
+
+    pub fn set_multisig_signers(ctx: Context<SetMultisigSigners>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::set_multisig_signers(ctx, signers, threshold)
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        target_program_id: Pubkey,
+        target_accounts: Vec<state::ProposalAccount>,
+        target_data: Vec<u8>
+    ) -> Result<()> {
+        instructions::create_proposal(ctx, target_program_id, target_accounts, target_data)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        instructions::approve_proposal(ctx)
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_proposal(ctx)
+    }
+
     // get function
 
-    pub fn get_pending_reward(ctx: Context<GetPendingReward>) -> Result<u64> {
+    pub fn get_pending_reward(ctx: Context<GetPendingReward>) -> Result<state::PendingRewardInfo> {
         instructions::get_pending_reward(ctx)
     }
 }
\ No newline at end of file
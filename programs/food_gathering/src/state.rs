@@ -1,34 +1,194 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
 
 #[account]
 #[derive(Default)]
 pub struct GlobalState {
     pub is_initialized: u8,
-    pub is_paused: bool,
+    pub paused_flags: u8,       // PAUSE_* operations that are currently paused
     pub owner: Pubkey,
-    pub vault: Pubkey,  // keep SOL from minters
-    pub ant_coin: Pubkey,
+    pub pending_owner: Pubkey,  // proposed by the owner, becomes owner once it accepts
+    pub vault: Pubkey,  // keep SOL
+    pub penalty_to_treasury: bool,  // early unlock penalties go to the treasury instead of being burned
+}
+
+impl GlobalState {
+    // true if any of the given operations is paused
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused_flags & flags != 0
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Pool {
+    pub pool_id: u64,
+    pub global_state: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub is_reward_stake_mint: bool, // rewards are paid in the staked mint, so they can be compounded
     pub precision: u32,
-    pub ant_food_token: Pubkey,
     pub stake_fee_amount: u64,
-    pub max_amount_for_stake: u64,
-    pub cycle_staked_amount: u64,
-    pub cycle_timestamp: u32,
-    pub antc_price: u64,
+    pub per_user_cap: u64,         // summed over all positions of a user
+    pub pool_cap: u64,             // total_staked can't go above it
+    pub reward_duration: i64,       // seconds a deposit is spread over
+    pub reward_rate: u64,           // reward per second, scaled by precision
+    pub period_finish: i64,         // rewards stop accruing at this time
+    pub antc_price: u64,            // manual price, used when there is no healthy price feed
     pub antc_expo: u64,
+    pub price_feed: Pubkey,         // Pyth ANTC/USD price account, default for none
+    pub max_price_age: i64,
+    pub max_price_conf_bps: u32,
+    pub acc_reward_per_share: u128, // scaled by ACC_REWARD_PRECISION
+    pub last_update_ts: i64,
+    pub total_staked: u64,
+    pub total_weight: u64,          // staked amount boosted by the lock tier multipliers
+    pub lock_tiers: [LockTier; MAX_LOCK_TIERS],
+    pub unstake_cooldown: i64,      // seconds between request_unstake and withdraw_unstaked
+    pub total_unbonding: u64,       // requested but not yet withdrawn
+    pub timelock_delay: i64,        // seconds a queued change waits before it can be executed
+    pub total_reward_owed: u128,    // accrued but not yet paid reward, scaled by precision
+    pub reward_streams: [RewardStream; MAX_REWARD_STREAMS],  // partner rewards paid next to ant food
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RewardStream {
+    pub mint: Pubkey,               // default for a free slot, slots aren't reused after retirement
+    pub is_active: bool,            // a retired stream stops emitting but still pays what was earned
+    pub reward_rate: u64,           // reward per second, scaled by precision
+    pub period_finish: i64,
+    pub acc_reward_per_share: u128, // scaled by ACC_REWARD_PRECISION
+    pub total_reward_owed: u128,    // accrued but not yet paid reward, scaled by precision
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LockTier {
+    pub lock_duration: i64,     // seconds, 0 means no lock
+    pub multiplier_bps: u32,    // reward multiplier, BPS_DENOMINATOR is 1x
+    pub early_unlock_penalty_bps: u32,  // 0 means the lock can't be left early
+    pub is_enabled: bool,
 }
 
 #[account]
 #[derive(Default)]
-pub struct Minter {
-    pub minter_key: Pubkey,
-    pub is_minter: bool,
+pub struct Role {
+    pub authority: Pubkey,
+    pub roles: u8,  // bitmask of the ROLE_* flags
+}
+
+impl Role {
+    pub fn has_role(&self, role: u8) -> bool {
+        self.roles & role == role
+    }
 }
 
 #[account]
 #[derive(Default)]
 pub struct StakedInfo {
+    pub position_id: u64,
     pub staked_amount: u64,
     pub staked_timestamp: i64,
-    pub reward_debt: u64,
+    pub reward_debt: u128,      // weight * acc_reward_per_share at the last update
+    pub unclaimed_reward: u64,  // reward kept for later payout, in precision units
+    pub owed_reward: u64,       // reward the vault couldn't cover yet, in reward tokens
+    pub stream_reward_debts: [u128; MAX_REWARD_STREAMS],
+    pub stream_unclaimed_rewards: [u64; MAX_REWARD_STREAMS],   // in precision units
+    pub lock_tier: u8,
+    pub lock_duration: i64,     // lock terms are fixed at stake time
+    pub multiplier_bps: u32,
+    pub early_unlock_penalty_bps: u32,
+    pub weight: u64,            // staked_amount * multiplier_bps / BPS_DENOMINATOR
+}
+
+#[account]
+#[derive(Default)]
+pub struct UserPositions {
+    pub position_count: u64,    // positions are numbered 0..position_count
+    pub staked_amount: u64,     // summed over all positions of the user
+}
+
+#[account]
+#[derive(Default)]
+pub struct UnstakeRequests {
+    pub tranches: [UnstakeTranche; MAX_UNSTAKE_TRANCHES],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct UnstakeTranche {
+    pub amount: u64,    // 0 means the slot is free
+    pub unlock_timestamp: i64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct PendingChange {
+    pub pool: Pubkey,
+    pub kind: u8,           // one of the CHANGE_* kinds
+    pub new_key: Pubkey,    // new mint for the mint changes
+    pub new_value: u64,
+    pub eta: i64,           // earliest execution time
+}
+
+#[account]
+#[derive(Default)]
+pub struct Multisig {
+    pub multisig_id: u64,
+    pub signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    pub signer_count: u8,
+    pub threshold: u8,
+    pub signers_seqno: u32, // bumped on every signer change, so older approvals go stale
+    pub proposal_count: u64,
+}
+
+impl Multisig {
+    pub fn signer_index(&self, key: &Pubkey) -> Option<usize> {
+        self.signers[..self.signer_count as usize]
+            .iter()
+            .position(|signer| signer == key)
+    }
+
+    pub fn set_signers(&mut self, signers: &[Pubkey], threshold: u8) {
+        self.signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+        self.signers[..signers.len()].copy_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.threshold = threshold;
+        self.signers_seqno += 1;
+    }
+}
+
+#[account]
+pub struct Proposal {
+    pub multisig: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub target_program_id: Pubkey,
+    pub target_accounts: Vec<ProposalAccount>,
+    pub target_data: Vec<u8>,
+    pub approvals: u16,     // bit i is set once signers[i] approved
+    pub signers_seqno: u32,
+    pub is_executed: bool,
+}
+
+impl Proposal {
+    pub fn space(accounts_len: usize, data_len: usize) -> usize {
+        8 + 32 + 8 + 32 + 32
+            + 4 + accounts_len * (32 + 1 + 1)
+            + 4 + data_len
+            + 2 + 4 + 1
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PendingRewardInfo {
+    pub pending_reward: u64,        // in precision units
+    pub stream_pending_rewards: [u64; MAX_REWARD_STREAMS],    // in precision units
+    pub early_unlock_penalty: u64,  // kept back if the whole stake is unstaked now
+    pub unlock_timestamp: i64,
 }
\ No newline at end of file
@@ -1,17 +1,486 @@
 use anchor_lang::prelude::*;
-use crate::{state::*};
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        self,
+        extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    },
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::{constants::*, errors::*, events::*, state::*};
 
-pub fn _get_pending_reward(global_state: &GlobalState, staked_info: &StakedInfo) -> Result<u64> {
+// reward per weight unit (scaled by ACC_REWARD_PRECISION) added since the last pool update by
+// a reward period emitting reward_rate per second, shared between all stakers by weight,
+// nothing is emitted after period_finish
+fn _get_acc_reward_increase(pool: &Pool, reward_rate: u64, period_finish: i64, now: i64) -> u128 {
+    let last_applicable_ts = now.min(period_finish);
+    if pool.total_weight == 0 || last_applicable_ts <= pool.last_update_ts {
+        return 0;
+    }
+    let elapsed = last_applicable_ts.checked_sub(pool.last_update_ts).unwrap() as u128;
+    elapsed
+        .checked_mul(reward_rate as u128).unwrap()
+        .checked_mul(ACC_REWARD_PRECISION).unwrap()
+        .checked_div(pool.total_weight as u128).unwrap()
+}
+
+// ant food reward per weight unit (scaled by ACC_REWARD_PRECISION) accumulated until `now`
+pub fn _get_acc_reward_per_share(pool: &Pool, now: i64) -> Result<u128> {
+    let reward_per_share = _get_acc_reward_increase(pool, pool.reward_rate, pool.period_finish, now);
+
+    Ok(pool.acc_reward_per_share.checked_add(reward_per_share).unwrap())
+}
+
+pub fn _get_stream_acc_reward_per_share(pool: &Pool, stream: &RewardStream, now: i64) -> u128 {
+    let reward_per_share = _get_acc_reward_increase(pool, stream.reward_rate, stream.period_finish, now);
+    stream.acc_reward_per_share.checked_add(reward_per_share).unwrap()
+}
+
+// reward scaled by precision outgrows a u64 for large deposits, so it's kept in a u128
+fn _get_emitted_reward(old_acc_reward_per_share: u128, acc_reward_per_share: u128, total_weight: u64) -> u128 {
+    (acc_reward_per_share - old_acc_reward_per_share)
+        .checked_mul(total_weight as u128).unwrap()
+        .checked_div(ACC_REWARD_PRECISION).unwrap()
+}
+
+pub fn _update_pool(pool: &mut Pool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let acc_reward_per_share = _get_acc_reward_per_share(pool, now)?;
+    let emitted_reward = _get_emitted_reward(pool.acc_reward_per_share, acc_reward_per_share, pool.total_weight);
+    pool.total_reward_owed = pool.total_reward_owed.checked_add(emitted_reward).unwrap();
+    pool.acc_reward_per_share = acc_reward_per_share;
+    for index in 0..MAX_REWARD_STREAMS {
+        let stream = pool.reward_streams[index];
+        if stream.mint == Pubkey::default() {
+            continue;
+        }
+        let acc_reward_per_share = _get_stream_acc_reward_per_share(pool, &stream, now);
+        let emitted_reward = _get_emitted_reward(stream.acc_reward_per_share, acc_reward_per_share, pool.total_weight);
+        pool.reward_streams[index].total_reward_owed = stream.total_reward_owed.checked_add(emitted_reward).unwrap();
+        pool.reward_streams[index].acc_reward_per_share = acc_reward_per_share;
+    }
+    pool.last_update_ts = now;
+
+    Ok(())
+}
+
+// reward scheduled but not yet emitted by a reward period, scaled by precision
+pub fn _get_remaining_reward(reward_rate: u64, period_finish: i64, now: i64) -> u128 {
+    if now >= period_finish {
+        return 0;
+    }
+    ((period_finish - now) as u128)
+        .checked_mul(reward_rate as u128).unwrap()
+}
+
+// reward tokens the vault has to keep for stakers and for the rest of the reward period, rounded up
+pub fn _get_reward_liability(pool: &Pool, now: i64) -> u64 {
+    let remaining_reward = _get_remaining_reward(pool.reward_rate, pool.period_finish, now);
+    _to_reward_tokens_rounded_up(pool, pool.total_reward_owed.checked_add(remaining_reward).unwrap())
+}
+
+pub fn _get_stream_reward_liability(pool: &Pool, stream: &RewardStream, now: i64) -> u64 {
+    let remaining_reward = _get_remaining_reward(stream.reward_rate, stream.period_finish, now);
+    _to_reward_tokens_rounded_up(pool, stream.total_reward_owed.checked_add(remaining_reward).unwrap())
+}
+
+// back in token units the reward was funded with, so it fits a u64 again
+fn _to_reward_tokens_rounded_up(pool: &Pool, reward: u128) -> u64 {
+    let precision = pool.precision as u128;
+    u64::try_from(reward.checked_add(precision - 1).unwrap() / precision).unwrap()
+}
+
+// part of the reward vault that can pay rewards, principal sharing the vault comes first
+pub fn _get_available_reward(pool: &Pool, vault_amount: u64) -> u64 {
+    if pool.is_reward_stake_mint {
+        vault_amount.saturating_sub(pool.total_staked + pool.total_unbonding)
+    } else {
+        vault_amount
+    }
+}
+
+pub fn _get_weight(staked_amount: u64, multiplier_bps: u32) -> u64 {
+    let weight = (staked_amount as u128)
+        .checked_mul(multiplier_bps as u128).unwrap()
+        .checked_div(BPS_DENOMINATOR as u128).unwrap();
+    u64::try_from(weight).unwrap()
+}
+
+pub fn _get_reward_debt(pool: &Pool, weight: u64) -> u128 {
+    (weight as u128)
+        .checked_mul(pool.acc_reward_per_share).unwrap()
+        .checked_div(ACC_REWARD_PRECISION).unwrap()
+}
+
+pub fn _get_pending_reward(pool: &Pool, staked_info: &StakedInfo) -> Result<u64> {
     let now = Clock::get()?.unix_timestamp;
-    let staked_period = now.checked_sub(staked_info.staked_timestamp).unwrap() as u64;
-    let pending_reward = staked_period
-        .checked_mul(staked_info.staked_amount).unwrap()
-        .checked_mul(global_state.precision as u64).unwrap()
-        .checked_div(
-            (global_state.cycle_timestamp as u64) * global_state.cycle_staked_amount
-        ).unwrap()
-        .checked_add(staked_info.reward_debt)
+    let acc_reward_per_share = _get_acc_reward_per_share(pool, now)?;
+    let accrued_reward = (staked_info.weight as u128)
+        .checked_mul(acc_reward_per_share).unwrap()
+        .checked_div(ACC_REWARD_PRECISION).unwrap()
+        .checked_sub(staked_info.reward_debt).unwrap();
+    let pending_reward = u64::try_from(accrued_reward).unwrap()
+        .checked_add(staked_info.unclaimed_reward)
         .unwrap();
-    
+
     Ok(pending_reward)
+}
+
+
+// what every reward stream earned for the position since its last update, in precision units
+pub fn _get_stream_pending_rewards(pool: &Pool, staked_info: &StakedInfo, now: i64) -> [u64; MAX_REWARD_STREAMS] {
+    let mut pending_rewards = staked_info.stream_unclaimed_rewards;
+    for (index, stream) in pool.reward_streams.iter().enumerate() {
+        if stream.mint == Pubkey::default() {
+            continue;
+        }
+        let acc_reward_per_share = _get_stream_acc_reward_per_share(pool, stream, now);
+        let accrued_reward = (staked_info.weight as u128)
+            .checked_mul(acc_reward_per_share).unwrap()
+            .checked_div(ACC_REWARD_PRECISION).unwrap()
+            .checked_sub(staked_info.stream_reward_debts[index]).unwrap();
+        pending_rewards[index] = pending_rewards[index].checked_add(u64::try_from(accrued_reward).unwrap()).unwrap();
+    }
+    pending_rewards
+}
+
+// moves what the reward streams earned into their unclaimed rewards, the pool has to be updated
+// and the weight of the position must not have changed yet
+pub fn _settle_stream_rewards(pool: &Pool, staked_info: &mut StakedInfo) {
+    staked_info.stream_unclaimed_rewards = _get_stream_pending_rewards(pool, staked_info, pool.last_update_ts);
+}
+
+// restarts the accrual of every reward stream from the current weight of the position
+pub fn _reset_stream_reward_debts(pool: &Pool, staked_info: &mut StakedInfo) {
+    for (index, stream) in pool.reward_streams.iter().enumerate() {
+        staked_info.stream_reward_debts[index] = (staked_info.weight as u128)
+            .checked_mul(stream.acc_reward_per_share).unwrap()
+            .checked_div(ACC_REWARD_PRECISION).unwrap();
+    }
+}
+
+// drops the unclaimed stream rewards of a position that is given up or closed
+pub fn _forfeit_stream_rewards(pool: &mut Pool, staked_info: &mut StakedInfo) {
+    for (index, stream) in pool.reward_streams.iter_mut().enumerate() {
+        stream.total_reward_owed = stream.total_reward_owed.saturating_sub(staked_info.stream_unclaimed_rewards[index] as u128);
+        staked_info.stream_unclaimed_rewards[index] = 0;
+    }
+}
+
+pub fn _has_stream_rewards(pool: &Pool, staked_info: &StakedInfo) -> bool {
+    staked_info.stream_unclaimed_rewards
+        .iter()
+        .any(|reward| *reward >= pool.precision as u64)
+}
+
+// pays the unclaimed reward of the reward streams, remaining_accounts holds a
+// (stream vault, user token account, stream mint, token program of the mint) group per stream
+// to pay, in stream order, a stream without a group is skipped, so a missing token account
+// never blocks the caller, what isn't paid or what a vault can't cover stays unclaimed
+pub fn _pay_stream_rewards<'info>(
+    pool: &mut Pool,
+    pool_key: Pubkey,
+    staked_info: &mut StakedInfo,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    global_state: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    program_id: &Pubkey,
+) -> Result<[u64; MAX_REWARD_STREAMS]> {
+    let precision = pool.precision as u64;
+    let mut reward_amounts = [0; MAX_REWARD_STREAMS];
+    let groups = remaining_accounts.chunks_exact(4);
+    require!(groups.remainder().is_empty(), FoodGatheringError::MissingRewardStreamAccounts);
+    let mut groups = groups.peekable();
+    for (index, stream) in pool.reward_streams.iter_mut().enumerate() {
+        if stream.mint == Pubkey::default() {
+            continue;
+        }
+        let (vault_info, user_info, mint_info, token_program_info) = match groups.next_if(|group| group[2].key() == stream.mint) {
+            Some(group) => (&group[0], &group[1], &group[2], &group[3]),
+            None => continue,
+        };
+        // each partner mint can live on spl-token or Token-2022, whichever owns it runs the transfer
+        let token_program = Interface::<TokenInterface>::try_from(token_program_info)?;
+        require!(*mint_info.owner == token_program.key(), FoodGatheringError::InvalidRewardStreamAccount);
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[TOKEN_VAULT_SEED, pool_key.as_ref(), stream.mint.as_ref()],
+            program_id
+        );
+        require!(vault_info.key() == vault_key, FoodGatheringError::InvalidRewardStreamAccount);
+        let vault = InterfaceAccount::<TokenAccount>::try_from(vault_info)?;
+        let user_account = InterfaceAccount::<TokenAccount>::try_from(user_info)?;
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        require!(user_account.mint == stream.mint, FoodGatheringError::InvalidRewardStreamAccount);
+
+        let reward_amount = (staked_info.stream_unclaimed_rewards[index] / precision).min(vault.amount);
+        if reward_amount == 0 {
+            continue;
+        }
+        let cpi_ctx = CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault_info.clone(),
+                mint: mint_info.clone(),
+                to: user_info.clone(),
+                authority: global_state.clone(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), reward_amount, mint.decimals)?;
+
+        staked_info.stream_unclaimed_rewards[index] -= reward_amount * precision;
+        stream.total_reward_owed = stream.total_reward_owed.saturating_sub((reward_amount * precision) as u128);
+        reward_amounts[index] = reward_amount;
+    }
+    // a group left over doesn't belong to any stream or isn't in stream order
+    require!(groups.next().is_none(), FoodGatheringError::InvalidRewardStreamAccount);
+
+    Ok(reward_amounts)
+}
+
+// settles the ant food the position earned until the last pool update, up to `available_reward`
+// is paid now and the rest is kept as an IOU, only the part below the precision stays unclaimed,
+// the caller restarts the accrual with _update_staked_amount, returns (reward_amount, shortfall)
+pub fn _settle_reward(pool: &mut Pool, staked_info: &mut StakedInfo, available_reward: u64) -> Result<(u64, u64)> {
+    let precision = pool.precision as u64;
+    let pending_reward = _get_pending_reward(pool, staked_info)?;
+    let earned_reward = pending_reward / precision;
+    let reward_amount = earned_reward.min(available_reward);
+    let shortfall = earned_reward - reward_amount;
+
+    staked_info.unclaimed_reward = pending_reward % precision;
+    staked_info.owed_reward += shortfall;
+    pool.total_reward_owed = pool.total_reward_owed.saturating_sub(reward_amount as u128 * precision as u128);
+
+    Ok((reward_amount, shortfall))
+}
+
+// settles the reward of the position and pays the ant food and the reward streams to the staker,
+// without a reward vault all of the ant food is kept as an IOU, returns the ant food paid
+#[allow(clippy::too_many_arguments)]
+pub fn _settle_and_pay_reward<'info>(
+    pool: &mut Account<'info, Pool>,
+    staked_info: &mut Account<'info, StakedInfo>,
+    staker: Pubkey,
+    global_state: &Account<'info, GlobalState>,
+    ant_food_token: &InterfaceAccount<'info, Mint>,
+    ant_food_token_vault_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    user_ant_food_token_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<u64> {
+    let available_reward = ant_food_token_vault_account
+        .map(|vault_account| _get_available_reward(pool, vault_account.amount))
+        .unwrap_or(0);
+    let (reward_amount, shortfall) = _settle_reward(pool, staked_info, available_reward)?;
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], &crate::ID);
+    let vault_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let signer = &[&vault_seeds[..]];
+
+    // transfer ant food token
+    if let Some(vault_account) = ant_food_token_vault_account.filter(|_| reward_amount > 0) {
+        let cpi_ctx = CpiContext::new(
+            token_program,
+            TransferChecked {
+                from: vault_account.to_account_info(),
+                mint: ant_food_token.to_account_info(),
+                to: user_ant_food_token_account,
+                authority: global_state.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), reward_amount, ant_food_token.decimals)?;
+    }
+
+    let pool_key = pool.key();
+    let stream_reward_amounts = _pay_stream_rewards(
+        pool,
+        pool_key,
+        staked_info,
+        remaining_accounts,
+        global_state.to_account_info(),
+        signer,
+        &crate::ID
+    )?;
+
+    if stream_reward_amounts.iter().any(|amount| *amount > 0) {
+        emit!(FoodGatheringStreamRewardsClaimed {
+            pool: pool_key,
+            staker,
+            reward_amounts: stream_reward_amounts
+        });
+    }
+
+    if shortfall > 0 {
+        emit!(FoodGatheringRewardShortfall {
+            pool: pool_key,
+            staker,
+            shortfall,
+            owed_reward: staked_info.owed_reward
+        });
+    }
+
+    Ok(reward_amount)
+}
+
+// sets the staked amount of the position and moves its weight in the pool with it, the accrual
+// restarts from the new weight, so what the position earned has to be settled first
+pub fn _update_staked_amount(pool: &mut Pool, staked_info: &mut StakedInfo, staked_amount: u64) {
+    let old_weight = staked_info.weight;
+    pool.total_staked = pool.total_staked - staked_info.staked_amount + staked_amount;
+    staked_info.staked_amount = staked_amount;
+    staked_info.weight = _get_weight(staked_amount, staked_info.multiplier_bps);
+    staked_info.reward_debt = _get_reward_debt(pool, staked_info.weight);
+    _reset_stream_reward_debts(pool, staked_info);
+    pool.total_weight = pool.total_weight - old_weight + staked_info.weight;
+}
+
+// part of `amount` kept back when it leaves a locked position before the lock ends
+pub fn _get_early_unlock_penalty(staked_info: &StakedInfo, amount: u64, now: i64) -> u64 {
+    if now >= staked_info.staked_timestamp + staked_info.lock_duration {
+        return 0;
+    }
+    let penalty = (amount as u128)
+        .checked_mul(staked_info.early_unlock_penalty_bps as u128).unwrap()
+        .checked_div(BPS_DENOMINATOR as u128).unwrap();
+    u64::try_from(penalty).unwrap()
+}
+
+// checks that `amount` may leave the position now and returns its early unlock penalty,
+// a locked position can only be left early if its tier has a penalty and the user accepted it
+pub fn _get_exit_penalty(staked_info: &StakedInfo, amount: u64, accept_penalty: bool, now: i64) -> Result<u64> {
+    let is_locked = now < staked_info.staked_timestamp + staked_info.lock_duration;
+    require!(
+        !is_locked || staked_info.early_unlock_penalty_bps > 0,
+        FoodGatheringError::StillLocked
+    );
+    require!(!is_locked || accept_penalty, FoodGatheringError::PenaltyNotAccepted);
+
+    Ok(_get_early_unlock_penalty(staked_info, amount, now))
+}
+
+// the penalty kept back in the stake vault is sent to the treasury or burned
+pub fn _take_early_unlock_penalty<'info>(
+    penalty_amount: u64,
+    global_state: &Account<'info, GlobalState>,
+    ant_coin: &InterfaceAccount<'info, Mint>,
+    ant_coin_vault_account: AccountInfo<'info>,
+    treasury_account: Option<AccountInfo<'info>>,
+    token_program: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if penalty_amount == 0 {
+        return Ok(());
+    }
+    if global_state.penalty_to_treasury {
+        let treasury_account = treasury_account.ok_or(FoodGatheringError::MissingTreasuryAccount)?;
+        let cpi_ctx = CpiContext::new(
+            token_program,
+            TransferChecked {
+                from: ant_coin_vault_account,
+                mint: ant_coin.to_account_info(),
+                to: treasury_account,
+                authority: global_state.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), penalty_amount, ant_coin.decimals)?;
+    } else {
+        let cpi_ctx = CpiContext::new(
+            token_program,
+            Burn {
+                mint: ant_coin.to_account_info(),
+                from: ant_coin_vault_account,
+                authority: global_state.to_account_info(),
+            },
+        );
+        burn(cpi_ctx.with_signer(signer), penalty_amount)?;
+    }
+
+    Ok(())
+}
+
+// (antc_price, antc_expo) so that 1 ant coin is worth antc_price / antc_expo USD
+// the feed is used while it's trading, fresh and tight enough, the manual price is the fallback
+pub fn _get_antc_price(pool: &Pool, price_feed: Option<&AccountInfo>, now: i64) -> Result<(u64, u64)> {
+    if pool.price_feed != Pubkey::default() {
+        let price_feed = price_feed.ok_or(FoodGatheringError::MissingPriceFeed)?;
+        let data = price_feed.try_borrow_data()?;
+        if let Some((price, conf, expo, publish_time)) = _read_pyth_price(&data) {
+            let is_fresh = now.checked_sub(publish_time).unwrap() <= pool.max_price_age;
+            let is_confident = (conf as u128) * (BPS_DENOMINATOR as u128)
+                <= (price as u128) * (pool.max_price_conf_bps as u128);
+            if is_fresh && is_confident {
+                return Ok((price, 10_u64.pow(expo)));
+            }
+        }
+        msg!("price feed is unavailable, falling back to the manual price");
+    }
+
+    require!(pool.antc_price > 0 && pool.antc_expo > 0, FoodGatheringError::PriceUnavailable);
+    Ok((pool.antc_price, pool.antc_expo))
+}
+
+// aggregate (price, conf, -expo, publish_time) of a Pyth price account, None if it isn't a trading price
+fn _read_pyth_price(data: &[u8]) -> Option<(u64, u64, u32, i64)> {
+    if data.len() < PYTH_PRICE_ACCOUNT_SIZE {
+        return None;
+    }
+    let read_u32 = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+    if read_u32(PYTH_MAGIC_OFFSET) != PYTH_MAGIC
+        || read_u32(PYTH_ACCOUNT_TYPE_OFFSET) != PYTH_ACCOUNT_TYPE_PRICE
+        || read_u32(PYTH_AGG_STATUS_OFFSET) != PYTH_STATUS_TRADING
+    {
+        return None;
+    }
+    let expo = read_u32(PYTH_EXPO_OFFSET) as i32;
+    let price = read_u64(PYTH_AGG_PRICE_OFFSET) as i64;
+    let conf = read_u64(PYTH_AGG_CONF_OFFSET);
+    let publish_time = read_u64(PYTH_TIMESTAMP_OFFSET) as i64;
+    if price <= 0 || expo > 0 || expo < -(MAX_PRICE_DECIMALS as i32) {
+        return None;
+    }
+
+    Some((price as u64, conf, (-expo) as u32, publish_time))
+}
+
+// role allowed to queue, execute or cancel a kind of pending change
+pub fn _get_change_role(kind: u8) -> Result<u8> {
+    match kind {
+        CHANGE_ANT_COIN | CHANGE_ANT_FOOD_TOKEN => Ok(ROLE_MINT_ADMIN),
+        CHANGE_PER_USER_CAP | CHANGE_POOL_CAP | CHANGE_TIMELOCK_DELAY => Ok(ROLE_PARAM_MANAGER),
+        _ => err!(FoodGatheringError::InvalidChange),
+    }
+}
+
+pub fn _validate_multisig_signers(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+        FoodGatheringError::InvalidMultisig
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        FoodGatheringError::InvalidMultisig
+    );
+    for (index, signer) in signers.iter().enumerate() {
+        require!(!signers[..index].contains(signer), FoodGatheringError::InvalidMultisig);
+    }
+
+    Ok(())
+}
+
+// part of `amount` withheld by a Token-2022 transfer fee mint, 0 for any other mint
+pub fn _get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let transfer_fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(FoodGatheringError::InvalidTransferFee)?,
+        Err(_) => 0,
+    };
+
+    Ok(transfer_fee)
 }
\ No newline at end of file
@@ -2,7 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { FoodGathering } from "../target/types/food_gathering";
 import { TOKEN_PROGRAM_ID, createAccount, createAssociatedTokenAccount, getAssociatedTokenAddress , ASSOCIATED_TOKEN_PROGRAM_ID,createMint, mintTo, mintToChecked, getAccount, getMint, getAssociatedTokenAddressSync } from "@solana/spl-token";
-import { SystemProgram, Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, ExtensionType, getMintLen, createInitializeTransferFeeConfigInstruction, createInitializeMintInstruction, createInitializePermanentDelegateInstruction, burn } from "@solana/spl-token";
+import { SystemProgram, Keypair, PublicKey, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import { assert } from "chai";
 
 describe("food_gathering", async () => {
   // Configure the client to use the local cluster.
@@ -12,6 +14,8 @@ describe("food_gathering", async () => {
   let connection = new anchor.web3.Connection("https://api.devnet.solana.com", "confirmed");
   let antCoin = new PublicKey("FLitGKEPBvBNqPVZbfgRPR5fwcsgSrRv6BDZjxRRFhUC"); 
   let antFood = new PublicKey("4JtesASQCh1ZYDdvCpgpMG5WLxMKyGAVt4tS4QS9L8Np"); 
+  // mock Pyth price account loaded from tests/fixtures on the local validator
+  let antcUsdPriceFeed = new PublicKey("DF8NKpdSZY9WTaqz6XS1N9G34PinDYxfgDKMDTchmSgA");
   //  2TYV72CtgYXCduE5hheeoux728zHcSyxPQAbhiCNf2Yy
   let owner = Keypair.fromSecretKey(
     Uint8Array.from([113,63,93,213,68,178,22,189,136,49,33,174,196,213,238,242,164,106,9,180,15,3,238,80,159,127,118,18,231,206,240,93,21,168,99,61,85,242,222,187,12,44,91,158,122,83,103,113,125,136,28,83,108,248,78,219,197,250,38,187,70,109,130,194])
@@ -19,8 +23,7 @@ describe("food_gathering", async () => {
   
   const [globalState, globalStateBump] = await anchor.web3.PublicKey.findProgramAddress(
     [
-      Buffer.from("GLOBAL-STATE-SEED"),
-      owner.publicKey.toBuffer()
+      Buffer.from("GLOBAL-STATE-SEED")
     ],
     program.programId
   );
@@ -32,17 +35,28 @@ describe("food_gathering", async () => {
     program.programId
   );
   
-  const [minter, minterBump] = await anchor.web3.PublicKey.findProgramAddress(
+  const [role, roleBump] = await anchor.web3.PublicKey.findProgramAddress(
     [
-      Buffer.from("MINTER-STATE-SEED"),
+      Buffer.from("ROLE-STATE-SEED"),
       owner.publicKey.toBuffer()
     ],
     program.programId
   );
 
+  const poolId = new anchor.BN(0);
+  const [pool, poolBump] = await anchor.web3.PublicKey.findProgramAddress(
+    [
+      Buffer.from("POOL-SEED"),
+      globalState.toBuffer(),
+      poolId.toArrayLike(Buffer, "le", 8)
+    ],
+    program.programId
+  );
+
   const [antFoodTokenVaultAccount, antFoodTokenVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
     [
       Buffer.from("TOKEN-VAULT-SEED"),
+      pool.toBuffer(),
       antFood.toBuffer()
     ],
     program.programId
@@ -51,22 +65,75 @@ describe("food_gathering", async () => {
   console.log(antFoodTokenVaultAccount.toString());
 
   const rentSysvar = anchor.web3.SYSVAR_RENT_PUBKEY;
+  // queued changes wait 48 hours unless a pool is created with a shorter delay
+  const timelockDelay = new anchor.BN(60 * 60 * 48);
   getTokenBalanceWeb3(connection, antFoodTokenVaultAccount);
 
   it("Is initialized!", async () => {
-    // Add your test here.
-    const antc_price = 735;
-    const antc_expo = 10000;
+    // only the upgrade authority of the program, the provider wallet that deployed it, can initialize
+    const [programData, programDataBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
+    let isInitializedByStranger = true;
+    try {
+      await program.rpc.initialize(
+        owner.publicKey,
+        {
+          accounts: {
+            owner: owner.publicKey,
+            globalState,
+            vault,
+            role,
+            program: program.programId,
+            programData,
+            systemProgram: SystemProgram.programId,
+            rent: rentSysvar
+          },
+          signers: [owner]
+        }
+      );
+    } catch (error) {
+      isInitializedByStranger = false;
+      assert.include(`${error}`, "NotAllowedOwner");
+    }
+    assert.isFalse(isInitializedByStranger);
+
     const tx = await program.rpc.initialize(
       owner.publicKey,
+      {
+        accounts: {
+          owner: program.provider.publicKey,
+          globalState,
+          vault,
+          role,
+          program: program.programId,
+          programData,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        }
+      }
+    )
+    console.log("Your transaction signature", tx);
+    assert.equal((await program.account.globalState.fetch(globalState)).owner.toString(), owner.publicKey.toString());
+  });
+
+  it("create pool", async () => {
+    const antc_price = 735;
+    const antc_expo = 10000;
+    const tx = await program.rpc.createPool(
+      poolId,
       new anchor.BN(antc_price),
       new anchor.BN(antc_expo),
+      timelockDelay,
       {
         accounts: {
           owner: owner.publicKey,
           globalState,
-          vault,
-          minter,
+          pool,
+          stakeMint: antCoin,
+          rewardMint: antFood,
           systemProgram: SystemProgram.programId,
           rent: rentSysvar
         },
@@ -76,37 +143,51 @@ describe("food_gathering", async () => {
     console.log("Your transaction signature", tx);
   });
 
-  it("set antc", async () => {
-    const tx = await program.rpc.setAntCoin({
+  it("queue and cancel antFood change", async () => {
+    const CHANGE_ANT_FOOD_TOKEN = 1;
+    const [pendingChange, pendingChangeBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("PENDING-CHANGE-SEED"),
+        pool.toBuffer(),
+        Buffer.from([CHANGE_ANT_FOOD_TOKEN])
+      ],
+      program.programId
+    );
+
+    const tx = await program.rpc.queueChange(
+      CHANGE_ANT_FOOD_TOKEN,
+      new anchor.BN(0),
+      {
       accounts: {
-        minterKey: owner.publicKey,
+        authority: owner.publicKey,
         globalState,
-        minter,
-        antCoin,
+        pool,
+        role,
+        pendingChange,
+        newMint: antFood,
         systemProgram: SystemProgram.programId,
         rent: rentSysvar
       },
       signers:[owner]
     });
-  });
-
+    console.log(tx);
 
-  it("set antFood", async () => {
-    const tx = await program.rpc.setAntFoodToken({
+    const cancelTx = await program.rpc.cancelChange({
       accounts: {
-        minterKey: owner.publicKey,
+        authority: owner.publicKey,
         globalState,
-        minter,
-        newAntFoodToken: antFood,
+        pool,
+        role,
+        pendingChange,
         systemProgram: SystemProgram.programId,
-        rent: rentSysvar
       },
       signers:[owner]
     });
+    console.log(cancelTx);
   });
   
   it("Deposit antFood", async () => {
-    const minterAntFoodTokenAccount = await getAssociatedTokenAddress(
+    const depositorAntFoodTokenAccount = await getAssociatedTokenAddress(
       antFood,
       owner.publicKey
     );
@@ -115,12 +196,13 @@ describe("food_gathering", async () => {
       new anchor.BN(1000000000),
       {
       accounts: {
-        minterKey: owner.publicKey,
+        authority: owner.publicKey,
         globalState,
-        minter,
+        pool,
+        role,
         antFoodToken: antFood,
         antFoodTokenVaultAccount,
-        minterAntFoodTokenAccount,
+        depositorAntFoodTokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: rentSysvar
@@ -130,6 +212,92 @@ describe("food_gathering", async () => {
     console.log(tx);
   });
 
+  it("set price feed", async () => {
+    // the mock price is published once, so accept any age here
+    const maxPriceAge = new anchor.BN("9223372036854775807");
+    const maxPriceConfBps = 200;
+    const tx = await program.rpc.setPriceFeed(
+      antcUsdPriceFeed,
+      maxPriceAge,
+      maxPriceConfBps,
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+    console.log(tx);
+  });
+
+  it("set lock tiers and the penalty route with the param manager role", async () => {
+    const ROLE_PARAM_MANAGER = 1 << 1;
+    const paramManager = Keypair.generate();
+    const [paramManagerRole, paramManagerRoleBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("ROLE-STATE-SEED"),
+        paramManager.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    const roleAccounts = {
+      owner: owner.publicKey,
+      globalState,
+      authority: paramManager.publicKey,
+      role: paramManagerRole,
+      systemProgram: SystemProgram.programId,
+      rent: rentSysvar
+    };
+    const setLockTier = () => program.rpc.setLockTier(
+      1,
+      new anchor.BN(0),
+      10000,
+      0,
+      false,
+      {
+      accounts: {
+        authority: paramManager.publicKey,
+        globalState,
+        pool,
+        role: paramManagerRole,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[paramManager]
+    });
+
+    await program.rpc.grantRole(ROLE_PARAM_MANAGER, { accounts: roleAccounts, signers:[owner] });
+    await setLockTier();
+    await program.rpc.setPenaltyToTreasury(
+      false,
+      {
+      accounts: {
+        authority: paramManager.publicKey,
+        globalState,
+        role: paramManagerRole,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[paramManager]
+    });
+    assert.isFalse((await program.account.pool.fetch(pool)).lockTiers[1].isEnabled);
+
+    await program.rpc.revokeRole(ROLE_PARAM_MANAGER, { accounts: roleAccounts, signers:[owner] });
+    let isLockTierSet = true;
+    try {
+      await setLockTier();
+    } catch (error) {
+      isLockTierSet = false;
+      assert.include(`${error}`, "NotAllowedRole");
+    }
+    assert.isFalse(isLockTierSet);
+  });
+
   it("stake antc",  async() => {
     
     const antCoin = new PublicKey("FLitGKEPBvBNqPVZbfgRPR5fwcsgSrRv6BDZjxRRFhUC");
@@ -137,6 +305,17 @@ describe("food_gathering", async () => {
     const [stakedInfo, stakeInfoBump] = await anchor.web3.PublicKey.findProgramAddress(
       [
         Buffer.from("STAKED-INFO-SEED"),
+        pool.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [userPositions, userPositionsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("USER-POSITIONS-SEED"),
+        pool.toBuffer(),
         owner.publicKey.toBuffer()
       ],
       program.programId
@@ -145,6 +324,7 @@ describe("food_gathering", async () => {
     const [antCoinVaultAccount, antCoinVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
       [
         Buffer.from("TOKEN-VAULT-SEED"),
+        pool.toBuffer(),
         antCoin.toBuffer()
       ],
       program.programId
@@ -155,29 +335,95 @@ describe("food_gathering", async () => {
       owner.publicKey
     );
 
-    try {
-      const tx = await program.rpc.stake(
-        new anchor.BN(100000000),
-        {
-        accounts: {
-          user: owner.publicKey,
-          globalState,
-          stakedInfo,
-          antCoin,
-          antCoinVaultAccount,
-          userAntCoinAccount,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        },
-        signers:[owner]
-      });
-      console.log("vault coin balance", await getTokenBalanceWeb3(connection, antCoinVaultAccount));
+    const localConnection = program.provider.connection;
+    const mintBefore = await getMint(localConnection, antCoin);
 
-      console.log("vault food balance", await getTokenBalanceWeb3(connection, antFoodTokenVaultAccount));
-      console.log(tx);
-    } catch (error) {
-      console.log(error);
-    }
+    const tx = await program.rpc.stake(
+      new anchor.BN(100000000),
+      0,
+      new anchor.BN(0),
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool,
+        userPositions,
+        stakedInfo,
+        antCoin,
+        antCoinVaultAccount,
+        userAntCoinAccount,
+        priceFeed: antcUsdPriceFeed,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    console.log("vault coin balance", await getTokenBalanceWeb3(connection, antCoinVaultAccount));
+
+    console.log("vault food balance", await getTokenBalanceWeb3(connection, antFoodTokenVaultAccount));
+    console.log(tx);
+
+    // the 2 USD fee is priced from the mock feed set above, 7_350_000 at expo -8 is 0.0735 USD per ant coin
+    const mintAfter = await getMint(localConnection, antCoin);
+    const expectedBurn = new anchor.BN(2)
+      .mul(new anchor.BN(10).pow(new anchor.BN(mintBefore.decimals)))
+      .mul(new anchor.BN(100_000_000))
+      .div(new anchor.BN(7_350_000));
+    assert.isTrue(expectedBurn.gtn(0));
+    const burned = new anchor.BN(mintBefore.supply.toString()).sub(new anchor.BN(mintAfter.supply.toString()));
+    assert.equal(burned.toString(), expectedBurn.toString());
+  });
+
+  it("claim reward",  async() => {
+
+    const antFood = new PublicKey("4JtesASQCh1ZYDdvCpgpMG5WLxMKyGAVt4tS4QS9L8Np");
+
+    const [stakedInfo, stakeInfoBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("STAKED-INFO-SEED"),
+        pool.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const userAntFoodTokenAccount = await getAssociatedTokenAddress(
+      antFood,
+      owner.publicKey
+    );
+
+    const localConnection = program.provider.connection;
+    const poolBefore = await program.account.pool.fetch(pool);
+    const balanceBefore = (await getAccount(localConnection, userAntFoodTokenAccount)).amount;
+
+    const tx = await program.rpc.claimReward(
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool,
+        stakedInfo,
+        antFoodToken: antFood,
+        antFoodTokenVaultAccount,
+        userAntFoodTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    console.log("vault food balance", await getTokenBalanceWeb3(connection, antFoodTokenVaultAccount));
+
+    console.log(tx);
+
+    // the only staker earns the whole emission since the stake updated the pool
+    const poolAfter = await program.account.pool.fetch(pool);
+    const balanceAfter = (await getAccount(localConnection, userAntFoodTokenAccount)).amount;
+    const expectedReward = poolBefore.rewardRate
+      .mul(poolAfter.lastUpdateTs.sub(poolBefore.lastUpdateTs))
+      .divn(poolBefore.precision);
+    const claimedReward = new anchor.BN(balanceAfter.toString()).sub(new anchor.BN(balanceBefore.toString()));
+    assert.equal(claimedReward.toString(), expectedReward.toString());
   });
 
   it("unstake antc",  async() => {
@@ -188,6 +434,17 @@ describe("food_gathering", async () => {
     const [stakedInfo, stakeInfoBump] = await anchor.web3.PublicKey.findProgramAddress(
       [
         Buffer.from("STAKED-INFO-SEED"),
+        pool.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [userPositions, userPositionsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("USER-POSITIONS-SEED"),
+        pool.toBuffer(),
         owner.publicKey.toBuffer()
       ],
       program.programId
@@ -196,6 +453,7 @@ describe("food_gathering", async () => {
     const [antCoinVaultAccount, antCoinVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
       [
         Buffer.from("TOKEN-VAULT-SEED"),
+        pool.toBuffer(),
         antCoin.toBuffer()
       ],
       program.programId
@@ -213,18 +471,24 @@ describe("food_gathering", async () => {
 
     try {
       const tx = await program.rpc.unstake(
+        new anchor.BN(50000000),
+        false,
         {
         accounts: {
           user: owner.publicKey,
           globalState,
+          pool,
+          userPositions,
           stakedInfo,
           antCoin,
           antCoinVaultAccount,
           userAntCoinAccount,
+          treasuryAccount: null,
           antFoodToken: antFood,
           antFoodTokenVaultAccount,
           userAntFoodTokenAccount,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          stakeTokenProgram: TOKEN_PROGRAM_ID,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         },
         signers:[owner]
@@ -238,7 +502,1670 @@ describe("food_gathering", async () => {
       console.log(error);
     }
   });
-});
+
+  it("stake a transfer fee Token-2022 mint", async () => {
+    const localConnection = program.provider.connection;
+    // Token-2022 mint withholding 1% of every transfer
+    const feeMint = await createTransferFeeMint(localConnection, owner, 5, 100);
+
+    const userFeeMintAccount = await createAssociatedTokenAccount(
+      localConnection,
+      owner,
+      feeMint.publicKey,
+      owner.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(localConnection, owner, feeMint.publicKey, userFeeMintAccount, owner, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    const feePoolId = new anchor.BN(1);
+    const [feePool, feePoolBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("POOL-SEED"),
+        globalState.toBuffer(),
+        feePoolId.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [userPositions, userPositionsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("USER-POSITIONS-SEED"),
+        feePool.toBuffer(),
+        owner.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    const [stakedInfo, stakeInfoBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("STAKED-INFO-SEED"),
+        feePool.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [feeMintVaultAccount, feeMintVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        feePool.toBuffer(),
+        feeMint.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    await program.rpc.createPool(
+      feePoolId,
+      new anchor.BN(735),
+      new anchor.BN(10000),
+      timelockDelay,
+      {
+        accounts: {
+          owner: owner.publicKey,
+          globalState,
+          pool: feePool,
+          stakeMint: feeMint.publicKey,
+          rewardMint: feeMint.publicKey,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        },
+        signers: [owner]
+      }
+    );
+
+    const tx = await program.rpc.stake(
+      new anchor.BN(10_000_000),
+      0,
+      new anchor.BN(0),
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: feePool,
+        userPositions,
+        stakedInfo,
+        antCoin: feeMint.publicKey,
+        antCoinVaultAccount: feeMintVaultAccount,
+        userAntCoinAccount: userFeeMintAccount,
+        priceFeed: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    console.log(tx);
+
+    // only the 9_900_000 left after the 1% fee reach the vault and are staked
+    const vaultAccount = await getAccount(localConnection, feeMintVaultAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    const stakedInfoAccount = await program.account.stakedInfo.fetch(stakedInfo);
+    assert.equal(vaultAccount.amount.toString(), "9900000");
+    assert.equal(stakedInfoAccount.stakedAmount.toString(), "9900000");
+  });
+
+  it("unstake from a pool with stake and reward mints on different token programs", async () => {
+    const localConnection = program.provider.connection;
+    // ant coin moved to Token-2022 while ant food stays on spl-token
+    const stakeMint = await createTransferFeeMint(localConnection, owner, 5, 100);
+    const rewardMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+
+    const userStakeAccount = await createAssociatedTokenAccount(
+      localConnection,
+      owner,
+      stakeMint.publicKey,
+      owner.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(localConnection, owner, stakeMint.publicKey, userStakeAccount, owner, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    const userRewardAccount = await createAssociatedTokenAccount(localConnection, owner, rewardMint, owner.publicKey);
+    await mintTo(localConnection, owner, rewardMint, userRewardAccount, owner, 1_000_000_000);
+
+    const mixedPoolId = new anchor.BN(2);
+    const [mixedPool, mixedPoolBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("POOL-SEED"),
+        globalState.toBuffer(),
+        mixedPoolId.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [userPositions, userPositionsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("USER-POSITIONS-SEED"),
+        mixedPool.toBuffer(),
+        owner.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    const [stakedInfo, stakeInfoBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("STAKED-INFO-SEED"),
+        mixedPool.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [stakeVaultAccount, stakeVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        mixedPool.toBuffer(),
+        stakeMint.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    const [rewardVaultAccount, rewardVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        mixedPool.toBuffer(),
+        rewardMint.toBuffer()
+      ],
+      program.programId
+    );
+
+    await program.rpc.createPool(
+      mixedPoolId,
+      new anchor.BN(735),
+      new anchor.BN(10000),
+      timelockDelay,
+      {
+        accounts: {
+          owner: owner.publicKey,
+          globalState,
+          pool: mixedPool,
+          stakeMint: stakeMint.publicKey,
+          rewardMint,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        },
+        signers: [owner]
+      }
+    );
+
+    await program.rpc.depositAntFoodToken(
+      new anchor.BN(1_000_000_000),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: mixedPool,
+        role,
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount: rewardVaultAccount,
+        depositorAntFoodTokenAccount: userRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    await program.rpc.stake(
+      new anchor.BN(10_000_000),
+      0,
+      new anchor.BN(0),
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: mixedPool,
+        userPositions,
+        stakedInfo,
+        antCoin: stakeMint.publicKey,
+        antCoinVaultAccount: stakeVaultAccount,
+        userAntCoinAccount: userStakeAccount,
+        priceFeed: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    // principal goes back through Token-2022, the reward through spl-token
+    const tx = await program.rpc.unstake(
+      new anchor.BN(9_900_000),
+      false,
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: mixedPool,
+        userPositions,
+        stakedInfo,
+        antCoin: stakeMint.publicKey,
+        antCoinVaultAccount: stakeVaultAccount,
+        userAntCoinAccount: userStakeAccount,
+        treasuryAccount: null,
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount: rewardVaultAccount,
+        userAntFoodTokenAccount: userRewardAccount,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    console.log(tx);
+
+    // the whole position left, so the vault is empty and the position was closed
+    const vaultAccount = await getAccount(localConnection, stakeVaultAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(vaultAccount.amount.toString(), "0");
+    assert.isNull(await program.account.stakedInfo.fetchNullable(stakedInfo));
+  });
+
+  it("fund and roll over reward periods that stop at period_finish", async () => {
+    const localConnection = program.provider.connection;
+    const stakeMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    const rewardMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    const userStakeAccount = await createAssociatedTokenAccount(localConnection, owner, stakeMint, owner.publicKey);
+    await mintTo(localConnection, owner, stakeMint, userStakeAccount, owner, 1_000_000_000);
+    const userRewardAccount = await createAssociatedTokenAccount(localConnection, owner, rewardMint, owner.publicKey);
+    await mintTo(localConnection, owner, rewardMint, userRewardAccount, owner, 1_000_000_000);
+
+    const periodPoolId = new anchor.BN(3);
+    const [periodPool, periodPoolBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("POOL-SEED"),
+        globalState.toBuffer(),
+        periodPoolId.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [userPositions, userPositionsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("USER-POSITIONS-SEED"),
+        periodPool.toBuffer(),
+        owner.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    const [stakedInfo, stakeInfoBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("STAKED-INFO-SEED"),
+        periodPool.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [stakeVaultAccount, stakeVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        periodPool.toBuffer(),
+        stakeMint.toBuffer()
+      ],
+      program.programId
+    );
+
+    const [rewardVaultAccount, rewardVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        periodPool.toBuffer(),
+        rewardMint.toBuffer()
+      ],
+      program.programId
+    );
+
+    await program.rpc.createPool(
+      periodPoolId,
+      new anchor.BN(735),
+      new anchor.BN(10000),
+      timelockDelay,
+      {
+        accounts: {
+          owner: owner.publicKey,
+          globalState,
+          pool: periodPool,
+          stakeMint,
+          rewardMint,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        },
+        signers: [owner]
+      }
+    );
+
+    const setRewardDuration = (rewardDuration: number) => program.rpc.setRewardDuration(
+      new anchor.BN(rewardDuration),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: periodPool,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    const deposit = (amount: number) => program.rpc.depositAntFoodToken(
+      new anchor.BN(amount),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: periodPool,
+        role,
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount: rewardVaultAccount,
+        depositorAntFoodTokenAccount: userRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    // returns what the claim paid
+    const claim = async () => {
+      const balanceBefore = (await getAccount(localConnection, userRewardAccount)).amount;
+      await program.rpc.claimReward({
+        accounts: {
+          user: owner.publicKey,
+          globalState,
+          pool: periodPool,
+          stakedInfo,
+          antFoodToken: rewardMint,
+          antFoodTokenVaultAccount: rewardVaultAccount,
+          userAntFoodTokenAccount: userRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        },
+        signers:[owner]
+      });
+      const balanceAfter = (await getAccount(localConnection, userRewardAccount)).amount;
+      return new anchor.BN(balanceAfter.toString()).sub(new anchor.BN(balanceBefore.toString()));
+    };
+
+    // a short period keeps the test quick, there is no running period yet so it can be set
+    const rewardDuration = 5;
+    await setRewardDuration(rewardDuration);
+
+    await program.rpc.stake(
+      new anchor.BN(100_000_000),
+      0,
+      new anchor.BN(0),
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: periodPool,
+        userPositions,
+        stakedInfo,
+        antCoin: stakeMint,
+        antCoinVaultAccount: stakeVaultAccount,
+        userAntCoinAccount: userStakeAccount,
+        priceFeed: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    // the deposit is spread evenly over the period, scaled by the precision
+    await deposit(1_000_000);
+    const firstPeriod = await program.account.pool.fetch(periodPool);
+    assert.equal(firstPeriod.rewardRate.toString(), new anchor.BN(1_000_000).muln(firstPeriod.precision).divn(rewardDuration).toString());
+    assert.equal(firstPeriod.periodFinish.toString(), firstPeriod.lastUpdateTs.addn(rewardDuration).toString());
+
+    // the duration of a running period can't change
+    let isDurationChanged = true;
+    try {
+      await setRewardDuration(10);
+    } catch (error) {
+      isDurationChanged = false;
+      assert.include(`${error}`, "RewardPeriodActive");
+    }
+    assert.isFalse(isDurationChanged);
+
+    // what's left of the running period rolls into the next one
+    await deposit(500_000);
+    const secondPeriod = await program.account.pool.fetch(periodPool);
+    const remainingReward = firstPeriod.periodFinish.sub(secondPeriod.lastUpdateTs).mul(firstPeriod.rewardRate);
+    const expectedRate = new anchor.BN(500_000).muln(secondPeriod.precision).add(remainingReward).divn(rewardDuration);
+    assert.equal(secondPeriod.rewardRate.toString(), expectedRate.toString());
+    assert.equal(secondPeriod.periodFinish.toString(), secondPeriod.lastUpdateTs.addn(rewardDuration).toString());
+
+    // claims on both sides of period_finish pay exactly what both periods emitted
+    const firstClaim = await claim();
+    await waitForTimestamp(localConnection, secondPeriod.periodFinish.toNumber());
+    const secondClaim = await claim();
+    const emittedReward = firstPeriod.rewardRate.mul(secondPeriod.lastUpdateTs.sub(firstPeriod.lastUpdateTs))
+      .add(secondPeriod.rewardRate.mul(secondPeriod.periodFinish.sub(secondPeriod.lastUpdateTs)));
+    assert.equal(firstClaim.add(secondClaim).toString(), emittedReward.divn(secondPeriod.precision).toString());
+
+    // nothing accrues after period_finish
+    await waitForTimestamp(localConnection, secondPeriod.periodFinish.toNumber() + 2);
+    assert.equal((await claim()).toString(), "0");
+  });
+
+  // a pool of its own on fresh mints, driven by the owner as staker and depositor,
+  // so a test doesn't depend on the state the other tests leave behind
+  const createOwnerPool = async (
+    id: number,
+    poolTimelockDelay: number,
+    rewardMint: PublicKey | null = null,
+    rewardTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    stakeMint: PublicKey | null = null
+  ) => {
+    const localConnection = program.provider.connection;
+    if (stakeMint == null) {
+      stakeMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    }
+    const userStakeAccount = await createAssociatedTokenAccount(localConnection, owner, stakeMint, owner.publicKey);
+    await mintTo(localConnection, owner, stakeMint, userStakeAccount, owner, 10_000_000_000);
+    if (rewardMint == null) {
+      rewardMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    }
+    let userRewardAccount = userStakeAccount;
+    if (!rewardMint.equals(stakeMint)) {
+      userRewardAccount = await createAssociatedTokenAccount(localConnection, owner, rewardMint, owner.publicKey, undefined, rewardTokenProgram);
+      await mintTo(localConnection, owner, rewardMint, userRewardAccount, owner, 10_000_000_000, [], undefined, rewardTokenProgram);
+    }
+
+    const poolId = new anchor.BN(id);
+    const [poolAddress, poolAddressBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("POOL-SEED"),
+        globalState.toBuffer(),
+        poolId.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+    const [userPositions, userPositionsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("USER-POSITIONS-SEED"),
+        poolAddress.toBuffer(),
+        owner.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+    const getVaultAccount = async (mint: PublicKey) => (await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        poolAddress.toBuffer(),
+        mint.toBuffer()
+      ],
+      program.programId
+    ))[0];
+    const stakeVaultAccount = await getVaultAccount(stakeMint);
+    const rewardVaultAccount = await getVaultAccount(rewardMint);
+    const getStakedInfo = async (positionId: number = 0) => (await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("STAKED-INFO-SEED"),
+        poolAddress.toBuffer(),
+        owner.publicKey.toBuffer(),
+        new anchor.BN(positionId).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    ))[0];
+    const getPendingChange = async (kind: number) => (await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("PENDING-CHANGE-SEED"),
+        poolAddress.toBuffer(),
+        Buffer.from([kind])
+      ],
+      program.programId
+    ))[0];
+
+    await program.rpc.createPool(
+      poolId,
+      new anchor.BN(735),
+      new anchor.BN(10000),
+      new anchor.BN(poolTimelockDelay),
+      {
+        accounts: {
+          owner: owner.publicKey,
+          globalState,
+          pool: poolAddress,
+          stakeMint,
+          rewardMint,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        },
+        signers: [owner]
+      }
+    );
+
+    const stake = async (amount: number, lockTier: number = 0, positionId: number = 0) => program.rpc.stake(
+      new anchor.BN(amount),
+      lockTier,
+      new anchor.BN(positionId),
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        userPositions,
+        stakedInfo: await getStakedInfo(positionId),
+        antCoin: stakeMint,
+        antCoinVaultAccount: stakeVaultAccount,
+        userAntCoinAccount: userStakeAccount,
+        priceFeed: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    const queueChange = async (kind: number, newValue: number, newMint: PublicKey | null = null) => program.rpc.queueChange(
+      kind,
+      new anchor.BN(newValue),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        role,
+        pendingChange: await getPendingChange(kind),
+        newMint,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    const executeChange = async (kind: number) => program.rpc.executeChange({
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        role,
+        pendingChange: await getPendingChange(kind),
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    const setRewardDuration = (rewardDuration: number) => program.rpc.setRewardDuration(
+      new anchor.BN(rewardDuration),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    const setLockTier = (lockTier: number, lockDuration: number, multiplierBps: number, earlyUnlockPenaltyBps: number) => program.rpc.setLockTier(
+      lockTier,
+      new anchor.BN(lockDuration),
+      multiplierBps,
+      earlyUnlockPenaltyBps,
+      true,
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    const deposit = (amount: number) => program.rpc.depositAntFoodToken(
+      new anchor.BN(amount),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        role,
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount: rewardVaultAccount,
+        depositorAntFoodTokenAccount: userRewardAccount,
+        tokenProgram: rewardTokenProgram,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    const unstake = async (
+      amount: number,
+      positionId: number = 0,
+      acceptPenalty: boolean = false,
+      treasuryAccount: PublicKey | null = null,
+      antFoodTokenVaultAccount: PublicKey | null = rewardVaultAccount
+    ) => program.rpc.unstake(
+      new anchor.BN(amount),
+      acceptPenalty,
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        stakedInfo: await getStakedInfo(positionId),
+        userPositions,
+        antCoin: stakeMint,
+        antCoinVaultAccount: stakeVaultAccount,
+        userAntCoinAccount: userStakeAccount,
+        treasuryAccount,
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount,
+        userAntFoodTokenAccount: userRewardAccount,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        rewardTokenProgram,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    // partner reward streams are paid from the groups passed as remaining accounts
+    const claim = async (positionId: number = 0, remainingAccounts: anchor.web3.AccountMeta[] = []) => program.rpc.claimReward({
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: poolAddress,
+        stakedInfo: await getStakedInfo(positionId),
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount: rewardVaultAccount,
+        userAntFoodTokenAccount: userRewardAccount,
+        tokenProgram: rewardTokenProgram,
+        systemProgram: SystemProgram.programId,
+      },
+      remainingAccounts,
+      signers:[owner]
+    });
+
+    return {
+      pool: poolAddress,
+      stakeMint,
+      rewardMint,
+      userStakeAccount,
+      userRewardAccount,
+      stakeVaultAccount,
+      rewardVaultAccount,
+      userPositions,
+      getVaultAccount,
+      getStakedInfo,
+      getPendingChange,
+      stake,
+      queueChange,
+      executeChange,
+      setRewardDuration,
+      setLockTier,
+      deposit,
+      unstake,
+      claim,
+    };
+  };
+
+  it("execute queued changes only after their eta and swap mints on empty pools", async () => {
+    const localConnection = program.provider.connection;
+    const CHANGE_ANT_COIN = 0;
+    const CHANGE_POOL_CAP = 4;
+    const timelockPool = await createOwnerPool(5, 4);
+
+    await timelockPool.queueChange(CHANGE_POOL_CAP, 500_000_000);
+    const { eta } = await program.account.pendingChange.fetch(await timelockPool.getPendingChange(CHANGE_POOL_CAP));
+
+    let isExecutedEarly = true;
+    try {
+      await timelockPool.executeChange(CHANGE_POOL_CAP);
+    } catch (error) {
+      isExecutedEarly = false;
+      assert.include(`${error}`, "ChangeNotReady");
+    }
+    assert.isFalse(isExecutedEarly);
+    assert.notEqual((await program.account.pool.fetch(timelockPool.pool)).poolCap.toString(), "500000000");
+
+    await waitForTimestamp(localConnection, eta.toNumber());
+    await timelockPool.executeChange(CHANGE_POOL_CAP);
+    assert.equal((await program.account.pool.fetch(timelockPool.pool)).poolCap.toString(), "500000000");
+
+    // the stake mint can't be swapped while principal sits in the vault of the old one
+    await timelockPool.stake(100_000_000);
+    const newStakeMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    await timelockPool.queueChange(CHANGE_ANT_COIN, 0, newStakeMint);
+    const mintChange = await program.account.pendingChange.fetch(await timelockPool.getPendingChange(CHANGE_ANT_COIN));
+    await waitForTimestamp(localConnection, mintChange.eta.toNumber());
+
+    let isMintSwapped = true;
+    try {
+      await timelockPool.executeChange(CHANGE_ANT_COIN);
+    } catch (error) {
+      isMintSwapped = false;
+      assert.include(`${error}`, "PoolNotEmpty");
+    }
+    assert.isFalse(isMintSwapped);
+    assert.equal((await program.account.pool.fetch(timelockPool.pool)).stakeMint.toString(), timelockPool.stakeMint.toString());
+  });
+
+  it("pay partner reward streams from the accounts passed with a claim", async () => {
+    const localConnection = program.provider.connection;
+    const CHANGE_ANT_FOOD_TOKEN = 1;
+    const streamPool = await createOwnerPool(6, 0);
+    const stakedInfo = await streamPool.getStakedInfo();
+
+    const partnerMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    const userPartnerAccount = await createAssociatedTokenAccount(localConnection, owner, partnerMint, owner.publicKey);
+    await mintTo(localConnection, owner, partnerMint, userPartnerAccount, owner, 1_000_000_000);
+    const secondPartnerMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    const userSecondPartnerAccount = await createAssociatedTokenAccount(localConnection, owner, secondPartnerMint, owner.publicKey);
+    await mintTo(localConnection, owner, secondPartnerMint, userSecondPartnerAccount, owner, 1_000_000_000);
+
+    const addRewardStream = async (mint: PublicKey) => program.rpc.addRewardStream({
+      accounts: {
+        owner: owner.publicKey,
+        globalState,
+        pool: streamPool.pool,
+        rewardMint: mint,
+        rewardVaultAccount: await streamPool.getVaultAccount(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+    const depositRewardStream = async (streamIndex: number, mint: PublicKey, depositorAccount: PublicKey, amount: number) => program.rpc.depositRewardStream(
+      streamIndex,
+      new anchor.BN(amount),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: streamPool.pool,
+        role,
+        rewardMint: mint,
+        rewardVaultAccount: await streamPool.getVaultAccount(mint),
+        depositorRewardTokenAccount: depositorAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    // vault, user account, mint and token program of a stream
+    const streamGroup = async (mint: PublicKey, userAccount: PublicKey) => [
+      { pubkey: await streamPool.getVaultAccount(mint), isSigner: false, isWritable: true },
+      { pubkey: userAccount, isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ];
+    const getBalance = async (tokenAccount: PublicKey) => new anchor.BN((await getAccount(localConnection, tokenAccount)).amount.toString());
+
+    // a stream can't share the vault of the stake mint
+    let isStakeMintAdded = true;
+    try {
+      await addRewardStream(streamPool.stakeMint);
+    } catch (error) {
+      isStakeMintAdded = false;
+      assert.include(`${error}`, "InvalidRewardStream");
+    }
+    assert.isFalse(isStakeMintAdded);
+
+    await addRewardStream(partnerMint);
+    await addRewardStream(secondPartnerMint);
+
+    // and ant food can't be switched over to the mint of a stream
+    await streamPool.queueChange(CHANGE_ANT_FOOD_TOKEN, 0, partnerMint);
+    let isSwitchedToStream = true;
+    try {
+      await streamPool.executeChange(CHANGE_ANT_FOOD_TOKEN);
+    } catch (error) {
+      isSwitchedToStream = false;
+      assert.include(`${error}`, "InvalidChange");
+    }
+    assert.isFalse(isSwitchedToStream);
+
+    // a short period keeps the test quick, the owner is the only staker and earns all of it
+    const rewardDuration = 5;
+    await streamPool.setRewardDuration(rewardDuration);
+    await streamPool.stake(100_000_000);
+    await streamPool.deposit(1_000_000);
+    await depositRewardStream(0, partnerMint, userPartnerAccount, 1_000_000);
+    await depositRewardStream(1, secondPartnerMint, userSecondPartnerAccount, 1_000_000);
+    const { rewardStreams, precision } = await program.account.pool.fetch(streamPool.pool);
+    await waitForTimestamp(localConnection, Math.max(rewardStreams[0].periodFinish.toNumber(), rewardStreams[1].periodFinish.toNumber()));
+
+    // a claim without the stream accounts leaves the stream reward owed
+    const partnerBalance = await getBalance(userPartnerAccount);
+    const secondPartnerBalance = await getBalance(userSecondPartnerAccount);
+    await streamPool.claim();
+    assert.equal((await getBalance(userPartnerAccount)).toString(), partnerBalance.toString());
+    assert.equal((await getBalance(userSecondPartnerAccount)).toString(), secondPartnerBalance.toString());
+    let position = await program.account.stakedInfo.fetch(stakedInfo);
+    assert.isAtMost(1_000_000 - position.streamUnclaimedRewards[0].divn(precision).toNumber(), 1);
+    assert.isAtMost(1_000_000 - position.streamUnclaimedRewards[1].divn(precision).toNumber(), 1);
+
+    // accounts are passed in groups of 4
+    const partnerGroup = await streamGroup(partnerMint, userPartnerAccount);
+    const secondPartnerGroup = await streamGroup(secondPartnerMint, userSecondPartnerAccount);
+    let isPartialGroupAccepted = true;
+    try {
+      await streamPool.claim(0, partnerGroup.slice(0, 3));
+    } catch (error) {
+      isPartialGroupAccepted = false;
+      assert.include(`${error}`, "MissingRewardStreamAccounts");
+    }
+    assert.isFalse(isPartialGroupAccepted);
+
+    // groups go in stream order, one that no stream picked up is left over
+    let isLeftoverGroupAccepted = true;
+    try {
+      await streamPool.claim(0, secondPartnerGroup.concat(partnerGroup));
+    } catch (error) {
+      isLeftoverGroupAccepted = false;
+      assert.include(`${error}`, "InvalidRewardStreamAccount");
+    }
+    assert.isFalse(isLeftoverGroupAccepted);
+
+    // a stream left out is skipped and keeps its reward
+    const owedStreamReward = position.streamUnclaimedRewards[0];
+    const secondStreamReward = position.streamUnclaimedRewards[1];
+    await streamPool.claim(0, secondPartnerGroup);
+    assert.equal((await getBalance(userPartnerAccount)).toString(), partnerBalance.toString());
+    assert.equal((await getBalance(userSecondPartnerAccount)).sub(secondPartnerBalance).toString(), secondStreamReward.divn(precision).toString());
+    position = await program.account.stakedInfo.fetch(stakedInfo);
+    assert.equal(position.streamUnclaimedRewards[0].toString(), owedStreamReward.toString());
+    assert.isBelow(position.streamUnclaimedRewards[1].toNumber(), precision);
+
+    // and is paid once its accounts are passed
+    await streamPool.claim(0, partnerGroup.concat(secondPartnerGroup));
+    assert.equal((await getBalance(userPartnerAccount)).sub(partnerBalance).toString(), owedStreamReward.divn(precision).toString());
+    position = await program.account.stakedInfo.fetch(stakedInfo);
+    assert.isBelow(position.streamUnclaimedRewards[0].toNumber(), precision);
+  });
+
+  it("keep what an under-funded reward vault can't pay as an IOU", async () => {
+    const localConnection = program.provider.connection;
+    // the owner is the permanent delegate of the reward mint, so it can drain the vault
+    const rewardMint = (await createPermanentDelegateMint(localConnection, owner, 6)).publicKey;
+    const iouPool = await createOwnerPool(7, 0, rewardMint, TOKEN_2022_PROGRAM_ID);
+    const stakedInfo = await iouPool.getStakedInfo();
+    const getBalance = async (tokenAccount: PublicKey, programId: PublicKey) => new anchor.BN((await getAccount(localConnection, tokenAccount, undefined, programId)).amount.toString());
+
+    // before the first deposit there is no reward vault, the principal can still leave
+    await iouPool.stake(100_000_000);
+    let stakeBalance = await getBalance(iouPool.userStakeAccount, TOKEN_PROGRAM_ID);
+    await iouPool.unstake(10_000_000, 0, false, null, null);
+    assert.equal((await getBalance(iouPool.userStakeAccount, TOKEN_PROGRAM_ID)).sub(stakeBalance).toString(), "10000000");
+
+    const rewardDuration = 5;
+    await iouPool.setRewardDuration(rewardDuration);
+    await iouPool.deposit(1_000_000);
+    const { periodFinish } = await program.account.pool.fetch(iouPool.pool);
+    await waitForTimestamp(localConnection, periodFinish.toNumber());
+
+    // the vault only covers 400_000 of the ~1_000_000 the owner earned
+    await burn(localConnection, owner, iouPool.rewardVaultAccount, rewardMint, owner, 600_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    stakeBalance = await getBalance(iouPool.userStakeAccount, TOKEN_PROGRAM_ID);
+    const rewardBalance = await getBalance(iouPool.userRewardAccount, TOKEN_2022_PROGRAM_ID);
+    await iouPool.unstake(90_000_000);
+
+    const paidReward = (await getBalance(iouPool.userRewardAccount, TOKEN_2022_PROGRAM_ID)).sub(rewardBalance);
+    assert.equal((await getBalance(iouPool.userStakeAccount, TOKEN_PROGRAM_ID)).sub(stakeBalance).toString(), "90000000");
+    assert.equal(paidReward.toString(), "400000");
+    // the shortfall is owed and keeps the position open
+    const { owedReward, stakedAmount } = await program.account.stakedInfo.fetch(stakedInfo);
+    assert.equal(stakedAmount.toString(), "0");
+    assert.isAtMost(1_000_000 - owedReward.add(paidReward).toNumber(), 1);
+    assert.isAbove(owedReward.toNumber(), 0);
+
+    const claimOwedReward = () => program.rpc.claimOwedReward({
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: iouPool.pool,
+        stakedInfo,
+        antFoodToken: rewardMint,
+        antFoodTokenVaultAccount: iouPool.rewardVaultAccount,
+        userAntFoodTokenAccount: iouPool.userRewardAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    let isPaidFromEmptyVault = true;
+    try {
+      await claimOwedReward();
+    } catch (error) {
+      isPaidFromEmptyVault = false;
+      assert.include(`${error}`, "InsufficientRewardBalance");
+    }
+    assert.isFalse(isPaidFromEmptyVault);
+
+    // once the vault is topped up the IOU is paid in full
+    await mintTo(localConnection, owner, rewardMint, iouPool.rewardVaultAccount, owner, 1_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    await claimOwedReward();
+    assert.equal((await getBalance(iouPool.userRewardAccount, TOKEN_2022_PROGRAM_ID)).sub(rewardBalance).toString(), paidReward.add(owedReward).toString());
+    assert.equal((await program.account.stakedInfo.fetch(stakedInfo)).owedReward.toString(), "0");
+  });
+
+  it("withdraw the whole principal without the reward while unstaking is paused", async () => {
+    const localConnection = program.provider.connection;
+    const PAUSE_UNSTAKE = 1 << 1;
+    const emergencyPool = await createOwnerPool(8, 0);
+    const stakedInfo = await emergencyPool.getStakedInfo();
+    const getBalance = async (tokenAccount: PublicKey) => new anchor.BN((await getAccount(localConnection, tokenAccount)).amount.toString());
+
+    await emergencyPool.setRewardDuration(5);
+    await emergencyPool.stake(100_000_000);
+    await emergencyPool.deposit(1_000_000);
+
+    const setPaused = (pausedFlags: number) => program.rpc.setPaused(
+      pausedFlags,
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+    const emergencyWithdraw = () => program.rpc.emergencyWithdraw({
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: emergencyPool.pool,
+        stakedInfo,
+        userPositions: emergencyPool.userPositions,
+        antCoin: emergencyPool.stakeMint,
+        antCoinVaultAccount: emergencyPool.stakeVaultAccount,
+        userAntCoinAccount: emergencyPool.userStakeAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    let isWithdrawnUnpaused = true;
+    try {
+      await emergencyWithdraw();
+    } catch (error) {
+      isWithdrawnUnpaused = false;
+      assert.include(`${error}`, "ConstraintRaw");
+    }
+    assert.isFalse(isWithdrawnUnpaused);
+
+    await setPaused(PAUSE_UNSTAKE);
+    const poolBefore = await program.account.pool.fetch(emergencyPool.pool);
+    const position = await program.account.stakedInfo.fetch(stakedInfo);
+    const stakedInfoRent = (await localConnection.getAccountInfo(stakedInfo)).lamports;
+    const ownerLamports = await localConnection.getBalance(owner.publicKey);
+    const stakeBalance = await getBalance(emergencyPool.userStakeAccount);
+    const rewardBalance = await getBalance(emergencyPool.userRewardAccount);
+    await emergencyWithdraw();
+    await setPaused(0);
+
+    // the full principal comes back, no reward is paid and the position is closed with its rent refunded
+    assert.equal((await getBalance(emergencyPool.userStakeAccount)).sub(stakeBalance).toString(), "100000000");
+    assert.equal((await getBalance(emergencyPool.userRewardAccount)).toString(), rewardBalance.toString());
+    assert.isNull(await program.account.stakedInfo.fetchNullable(stakedInfo));
+    assert.equal(await localConnection.getBalance(owner.publicKey) - ownerLamports, stakedInfoRent);
+
+    const poolAfter = await program.account.pool.fetch(emergencyPool.pool);
+    assert.equal(poolBefore.totalStaked.sub(poolAfter.totalStaked).toString(), "100000000");
+    assert.equal(poolBefore.totalWeight.sub(poolAfter.totalWeight).toString(), position.weight.toString());
+    assert.equal(poolAfter.totalStaked.toString(), "0");
+    // the forfeited reward isn't owed anymore, only rounding below the precision is left
+    assert.isBelow(poolAfter.totalRewardOwed.toNumber(), poolAfter.precision);
+  });
+
+  it("hold locked positions and share the reward by the tier multipliers", async () => {
+    const localConnection = program.provider.connection;
+    const lockPool = await createOwnerPool(9, 0);
+    const getBalance = async (tokenAccount: PublicKey) => new anchor.BN((await getAccount(localConnection, tokenAccount)).amount.toString());
+
+    // tier 1 locks for an hour without a way out, tier 2 doesn't lock and earns 2x
+    await lockPool.setLockTier(1, 3600, 10000, 0);
+    await lockPool.setLockTier(2, 0, 20000, 0);
+    await lockPool.setRewardDuration(5);
+    await lockPool.stake(100_000_000, 1, 0);
+    await lockPool.stake(100_000_000, 2, 1);
+
+    let isUnstakedLocked = true;
+    try {
+      // nothing is deposited yet, so there is no reward vault to pass
+      await lockPool.unstake(100_000_000, 0, false, null, null);
+    } catch (error) {
+      isUnstakedLocked = false;
+      assert.include(`${error}`, "StillLocked");
+    }
+    assert.isFalse(isUnstakedLocked);
+
+    const lockedPosition = await program.account.stakedInfo.fetch(await lockPool.getStakedInfo(0));
+    const boostedPosition = await program.account.stakedInfo.fetch(await lockPool.getStakedInfo(1));
+    assert.equal(lockedPosition.weight.toString(), "100000000");
+    assert.equal(boostedPosition.weight.toString(), "200000000");
+    assert.equal((await program.account.pool.fetch(lockPool.pool)).totalWeight.toString(), "300000000");
+
+    // both positions earn over the whole period, so the 2x position gets two thirds of it
+    await lockPool.deposit(1_200_000);
+    const { periodFinish } = await program.account.pool.fetch(lockPool.pool);
+    await waitForTimestamp(localConnection, periodFinish.toNumber());
+
+    const rewardBalance = await getBalance(lockPool.userRewardAccount);
+    await lockPool.claim(0);
+    const lockedReward = (await getBalance(lockPool.userRewardAccount)).sub(rewardBalance);
+    await lockPool.claim(1);
+    const boostedReward = (await getBalance(lockPool.userRewardAccount)).sub(rewardBalance).sub(lockedReward);
+    assert.isAtMost(Math.abs(lockedReward.toNumber() - 400_000), 1);
+    assert.isAtMost(Math.abs(boostedReward.toNumber() - 800_000), 1);
+  });
+
+  it("take the early unlock penalty and burn it or send it to the treasury", async () => {
+    const localConnection = program.provider.connection;
+    const penaltyPool = await createOwnerPool(10, 0);
+    const getBalance = async (tokenAccount: PublicKey) => new anchor.BN((await getAccount(localConnection, tokenAccount)).amount.toString());
+    const getSupply = async () => new anchor.BN((await getMint(localConnection, penaltyPool.stakeMint)).supply.toString());
+
+    // an hour long lock that can be left early for 10%, no reward is deposited so there is no reward vault
+    await penaltyPool.setLockTier(1, 3600, 10000, 1000);
+    await penaltyPool.stake(100_000_000, 1, 0);
+
+    let isLeftWithoutPenalty = true;
+    try {
+      await penaltyPool.unstake(10_000_000, 0, false, null, null);
+    } catch (error) {
+      isLeftWithoutPenalty = false;
+      assert.include(`${error}`, "PenaltyNotAccepted");
+    }
+    assert.isFalse(isLeftWithoutPenalty);
+
+    // by default the penalty is burned
+    let stakeBalance = await getBalance(penaltyPool.userStakeAccount);
+    let supply = await getSupply();
+    await penaltyPool.unstake(10_000_000, 0, true, null, null);
+    assert.equal((await getBalance(penaltyPool.userStakeAccount)).sub(stakeBalance).toString(), "9000000");
+    assert.equal(supply.sub(await getSupply()).toString(), "1000000");
+
+    const setPenaltyToTreasury = (penaltyToTreasury: boolean) => program.rpc.setPenaltyToTreasury(
+      penaltyToTreasury,
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+    const [treasuryAccount, treasuryAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TREASURY-SEED"),
+        penaltyPool.stakeMint.toBuffer()
+      ],
+      program.programId
+    );
+    await program.rpc.initTreasury({
+      accounts: {
+        owner: owner.publicKey,
+        globalState,
+        tokenMint: penaltyPool.stakeMint,
+        treasuryAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    // routed to the treasury nothing is burned
+    await setPenaltyToTreasury(true);
+    stakeBalance = await getBalance(penaltyPool.userStakeAccount);
+    supply = await getSupply();
+    await penaltyPool.unstake(10_000_000, 0, true, treasuryAccount, null);
+    await setPenaltyToTreasury(false);
+    assert.equal((await getBalance(penaltyPool.userStakeAccount)).sub(stakeBalance).toString(), "9000000");
+    assert.equal((await getBalance(treasuryAccount)).toString(), "1000000");
+    assert.equal((await getSupply()).toString(), supply.toString());
+  });
+
+  it("queue unstake requests behind the cooldown", async () => {
+    const localConnection = program.provider.connection;
+    const cooldownPool = await createOwnerPool(11, 0);
+    const getBalance = async (tokenAccount: PublicKey) => new anchor.BN((await getAccount(localConnection, tokenAccount)).amount.toString());
+    const [unstakeRequests, unstakeRequestsBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("UNSTAKE-REQUEST-SEED"),
+        cooldownPool.pool.toBuffer(),
+        owner.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+
+    await program.rpc.setUnstakeCooldown(
+      new anchor.BN(4),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: cooldownPool.pool,
+        role,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+    await cooldownPool.stake(100_000_000);
+
+    const requestUnstake = async (amount: number) => program.rpc.requestUnstake(
+      new anchor.BN(amount),
+      false,
+      {
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: cooldownPool.pool,
+        stakedInfo: await cooldownPool.getStakedInfo(),
+        userPositions: cooldownPool.userPositions,
+        unstakeRequests,
+        antCoin: cooldownPool.stakeMint,
+        antCoinVaultAccount: cooldownPool.stakeVaultAccount,
+        treasuryAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    const withdrawUnstaked = () => program.rpc.withdrawUnstaked({
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: cooldownPool.pool,
+        unstakeRequests,
+        antCoin: cooldownPool.stakeMint,
+        antCoinVaultAccount: cooldownPool.stakeVaultAccount,
+        userAntCoinAccount: cooldownPool.userStakeAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    // with a cooldown set the principal can only leave through a request
+    let isUnstakedDirectly = true;
+    try {
+      await cooldownPool.unstake(10_000_000, 0, false, null, null);
+    } catch (error) {
+      isUnstakedDirectly = false;
+      assert.include(`${error}`, "CooldownRequired");
+    }
+    assert.isFalse(isUnstakedDirectly);
+
+    await requestUnstake(10_000_000);
+    let isWithdrawnEarly = true;
+    try {
+      await withdrawUnstaked();
+    } catch (error) {
+      isWithdrawnEarly = false;
+      assert.include(`${error}`, "NothingToWithdraw");
+    }
+    assert.isFalse(isWithdrawnEarly);
+
+    const { tranches } = await program.account.unstakeRequests.fetch(unstakeRequests);
+    await waitForTimestamp(localConnection, tranches[0].unlockTimestamp.toNumber());
+    const stakeBalance = await getBalance(cooldownPool.userStakeAccount);
+    await withdrawUnstaked();
+    assert.equal((await getBalance(cooldownPool.userStakeAccount)).sub(stakeBalance).toString(), "10000000");
+
+    // at most 8 requests wait at a time
+    for (let i = 0; i < 8; i++) {
+      await requestUnstake(1_000_000);
+    }
+    let isNinthRequested = true;
+    try {
+      await requestUnstake(1_000_000);
+    } catch (error) {
+      isNinthRequested = false;
+      assert.include(`${error}`, "TooManyUnstakeRequests");
+    }
+    assert.isFalse(isNinthRequested);
+  });
+
+  it("compound the reward into the stake when both share a mint", async () => {
+    const localConnection = program.provider.connection;
+    const compoundMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    const compoundPool = await createOwnerPool(12, 0, compoundMint, TOKEN_PROGRAM_ID, compoundMint);
+    const stakedInfo = await compoundPool.getStakedInfo();
+
+    await compoundPool.setRewardDuration(5);
+    await compoundPool.stake(100_000_000);
+    await compoundPool.deposit(1_000_000);
+    const { periodFinish } = await program.account.pool.fetch(compoundPool.pool);
+    await waitForTimestamp(localConnection, periodFinish.toNumber());
+
+    const poolBefore = await program.account.pool.fetch(compoundPool.pool);
+    const vaultBalance = (await getAccount(localConnection, compoundPool.stakeVaultAccount)).amount;
+    await program.rpc.compound({
+      accounts: {
+        user: owner.publicKey,
+        globalState,
+        pool: compoundPool.pool,
+        stakedInfo,
+        userPositions: compoundPool.userPositions,
+        antCoinVaultAccount: compoundPool.stakeVaultAccount,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+
+    // the owner was the only staker, so all of the period's reward is added to the stake
+    const position = await program.account.stakedInfo.fetch(stakedInfo);
+    const compoundedReward = position.stakedAmount.subn(100_000_000);
+    assert.isAtMost(1_000_000 - compoundedReward.toNumber(), 1);
+    assert.isBelow(position.unclaimedReward.toNumber(), poolBefore.precision);
+    assert.equal((await program.account.pool.fetch(compoundPool.pool)).totalStaked.sub(poolBefore.totalStaked).toString(), compoundedReward.toString());
+    assert.equal((await program.account.userPositions.fetch(compoundPool.userPositions)).stakedAmount.toString(), position.stakedAmount.toString());
+    // no tokens move, the reward already sits in the stake vault
+    assert.equal((await getAccount(localConnection, compoundPool.stakeVaultAccount)).amount.toString(), vaultBalance.toString());
+  });
+
+  it("stake up to the per user and pool caps but not beyond", async () => {
+    const CHANGE_PER_USER_CAP = 2;
+    const CHANGE_POOL_CAP = 4;
+    const capPool = await createOwnerPool(13, 0);
+    const setCap = async (kind: number, cap: number) => {
+      await capPool.queueChange(kind, cap);
+      await capPool.executeChange(kind);
+    };
+
+    await setCap(CHANGE_PER_USER_CAP, 100_000_000);
+    await capPool.stake(100_000_000);
+    let isUserCapExceeded = true;
+    try {
+      await capPool.stake(1);
+    } catch (error) {
+      isUserCapExceeded = false;
+      assert.include(`${error}`, "UserStakingCapAttained");
+    }
+    assert.isFalse(isUserCapExceeded);
+
+    // with the user cap out of the way the pool cap is the limit
+    await setCap(CHANGE_PER_USER_CAP, 1_000_000_000);
+    await setCap(CHANGE_POOL_CAP, 150_000_000);
+    await capPool.stake(50_000_000);
+    let isPoolCapExceeded = true;
+    try {
+      await capPool.stake(1);
+    } catch (error) {
+      isPoolCapExceeded = false;
+      assert.include(`${error}`, "MaxStakingAmountAttained");
+    }
+    assert.isFalse(isPoolCapExceeded);
+    assert.equal((await program.account.pool.fetch(capPool.pool)).totalStaked.toString(), "150000000");
+  });
+
+  it("hand ownership over with propose_owner and accept_owner", async () => {
+    const localConnection = program.provider.connection;
+    const newOwner = Keypair.generate();
+    const stranger = Keypair.generate();
+    await sendAndConfirmTransaction(
+      localConnection,
+      new Transaction().add(
+        SystemProgram.transfer({ fromPubkey: owner.publicKey, toPubkey: newOwner.publicKey, lamports: 100_000_000 }),
+        SystemProgram.transfer({ fromPubkey: owner.publicKey, toPubkey: stranger.publicKey, lamports: 100_000_000 })
+      ),
+      [owner]
+    );
+
+    const getRole = async (authority: PublicKey) => (await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("ROLE-STATE-SEED"),
+        authority.toBuffer()
+      ],
+      program.programId
+    ))[0];
+    const newOwnerRole = await getRole(newOwner.publicKey);
+    const strangerRole = await getRole(stranger.publicKey);
+
+    const proposeOwner = (currentOwner: Keypair, pendingOwner: PublicKey) => program.rpc.proposeOwner(
+      pendingOwner,
+      {
+      accounts: {
+        owner: currentOwner.publicKey,
+        globalState,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[currentOwner]
+    });
+    const acceptOwner = (pendingOwner: Keypair, previousOwnerRole: PublicKey, pendingOwnerRole: PublicKey) => program.rpc.acceptOwner({
+      accounts: {
+        pendingOwner: pendingOwner.publicKey,
+        globalState,
+        previousOwnerRole,
+        role: pendingOwnerRole,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[pendingOwner]
+    });
+
+    await proposeOwner(owner, newOwner.publicKey);
+
+    // only the proposed key can accept
+    let isAcceptedByStranger = true;
+    try {
+      await acceptOwner(stranger, role, strangerRole);
+    } catch (error) {
+      isAcceptedByStranger = false;
+      assert.include(`${error}`, "ConstraintHasOne");
+    }
+    assert.isFalse(isAcceptedByStranger);
+    assert.equal((await program.account.globalState.fetch(globalState)).owner.toString(), owner.publicKey.toString());
+
+    // the roles move with the ownership
+    await acceptOwner(newOwner, role, newOwnerRole);
+    assert.equal((await program.account.globalState.fetch(globalState)).owner.toString(), newOwner.publicKey.toString());
+    assert.equal((await program.account.role.fetch(newOwnerRole)).roles, 31);
+    assert.equal((await program.account.role.fetch(role)).roles, 0);
+
+    // hand it back so the rest of the tests keep running as the owner
+    await proposeOwner(newOwner, owner.publicKey);
+    await acceptOwner(owner, newOwnerRole, role);
+    assert.equal((await program.account.globalState.fetch(globalState)).owner.toString(), owner.publicKey.toString());
+    assert.equal((await program.account.role.fetch(role)).roles, 31);
+    assert.equal((await program.account.role.fetch(newOwnerRole)).roles, 0);
+  });
+
+  it("run owner actions through a multisig proposal", async () => {
+    const localConnection = program.provider.connection;
+    const secondSigner = Keypair.generate();
+    const thirdSigner = Keypair.generate();
+
+    const multisigId = new anchor.BN(0);
+    const [multisig, multisigBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("MULTISIG-SEED"),
+        multisigId.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [multisigSigner, multisigSignerBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("MULTISIG-SIGNER-SEED"),
+        multisig.toBuffer()
+      ],
+      program.programId
+    );
+
+    const [multisigSignerRole, multisigSignerRoleBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("ROLE-STATE-SEED"),
+        multisigSigner.toBuffer()
+      ],
+      program.programId
+    );
+
+    await program.rpc.createMultisig(
+      multisigId,
+      [owner.publicKey, secondSigner.publicKey, thirdSigner.publicKey],
+      2,
+      {
+      accounts: {
+        payer: owner.publicKey,
+        multisig,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+
+    // the signer PDA pays for the accounts its proposals create
+    await sendAndConfirmTransaction(
+      localConnection,
+      new Transaction().add(
+        SystemProgram.transfer({ fromPubkey: owner.publicKey, toPubkey: multisigSigner, lamports: 100_000_000 })
+      ),
+      [owner]
+    );
+
+    // a pool whose vault holds a surplus above the reward it owes
+    const treasuryMint = await createMint(localConnection, owner, owner.publicKey, null, 6);
+    const ownerTreasuryMintAccount = await createAssociatedTokenAccount(localConnection, owner, treasuryMint, owner.publicKey);
+    await mintTo(localConnection, owner, treasuryMint, ownerTreasuryMintAccount, owner, 1_000_000_000);
+
+    const treasuryPoolId = new anchor.BN(4);
+    const [treasuryPool, treasuryPoolBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("POOL-SEED"),
+        globalState.toBuffer(),
+        treasuryPoolId.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+
+    const [treasuryVaultAccount, treasuryVaultAccountBump] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("TOKEN-VAULT-SEED"),
+        treasuryPool.toBuffer(),
+        treasuryMint.toBuffer()
+      ],
+      program.programId
+    );
+
+    await program.rpc.createPool(
+      treasuryPoolId,
+      new anchor.BN(735),
+      new anchor.BN(10000),
+      timelockDelay,
+      {
+        accounts: {
+          owner: owner.publicKey,
+          globalState,
+          pool: treasuryPool,
+          stakeMint: treasuryMint,
+          rewardMint: treasuryMint,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        },
+        signers: [owner]
+      }
+    );
+
+    await program.rpc.depositAntFoodToken(
+      new anchor.BN(1_000_000),
+      {
+      accounts: {
+        authority: owner.publicKey,
+        globalState,
+        pool: treasuryPool,
+        role,
+        antFoodToken: treasuryMint,
+        antFoodTokenVaultAccount: treasuryVaultAccount,
+        depositorAntFoodTokenAccount: ownerTreasuryMintAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: rentSysvar
+      },
+      signers:[owner]
+    });
+    await mintTo(localConnection, owner, treasuryMint, treasuryVaultAccount, owner, 5_000_000);
+
+    // proposes the instruction, checks it can't run on the proposer's approval alone
+    // and runs it once a second signer reached the 2 of 3 threshold
+    const runProposal = async (ix: anchor.web3.TransactionInstruction) => {
+      const multisigAccount = await program.account.multisig.fetch(multisig);
+      const [proposal, proposalBump] = await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("PROPOSAL-SEED"),
+          multisig.toBuffer(),
+          multisigAccount.proposalCount.toArrayLike(Buffer, "le", 8)
+        ],
+        program.programId
+      );
+
+      await program.rpc.createProposal(
+        ix.programId,
+        ix.keys.map((key) => ({ pubkey: key.pubkey, isSigner: key.isSigner, isWritable: key.isWritable })),
+        ix.data,
+        {
+        accounts: {
+          proposer: owner.publicKey,
+          multisig,
+          proposal,
+          systemProgram: SystemProgram.programId,
+          rent: rentSysvar
+        },
+        signers:[owner]
+      });
+
+      // the signer PDA only signs inside execute_proposal
+      const remainingAccounts = ix.keys
+        .map((key) => ({ pubkey: key.pubkey, isSigner: false, isWritable: key.isWritable }))
+        .concat([{ pubkey: ix.programId, isSigner: false, isWritable: false }]);
+      const execute = () => program.rpc.executeProposal({
+        accounts: {
+          executor: owner.publicKey,
+          multisig,
+          proposal,
+        },
+        remainingAccounts,
+        signers:[owner]
+      });
+
+      let isExecuted = true;
+      try {
+        await execute();
+      } catch (error) {
+        isExecuted = false;
+        assert.include(`${error}`, "NotEnoughApprovals");
+      }
+      assert.isFalse(isExecuted);
+
+      await program.rpc.approveProposal({
+        accounts: {
+          approver: secondSigner.publicKey,
+          multisig,
+          proposal,
+        },
+        signers:[secondSigner]
+      });
+      await execute();
+      assert.isTrue((await program.account.proposal.fetch(proposal)).isExecuted);
+    };
+
+    // hand the ownership and its roles to the multisig
+    await program.rpc.proposeOwner(
+      multisigSigner,
+      {
+      accounts: {
+        owner: owner.publicKey,
+        globalState,
+        systemProgram: SystemProgram.programId,
+      },
+      signers:[owner]
+    });
+    await runProposal(await program.methods.acceptOwner().accounts({
+      pendingOwner: multisigSigner,
+      globalState,
+      previousOwnerRole: role,
+      role: multisigSignerRole,
+      systemProgram: SystemProgram.programId,
+    }).instruction());
+    assert.equal((await program.account.globalState.fetch(globalState)).owner.toString(), multisigSigner.toString());
+
+    const PAUSE_CLAIM = 1 << 2;
+    await runProposal(await program.methods.setPaused(PAUSE_CLAIM).accounts({
+      authority: multisigSigner,
+      globalState,
+      role: multisigSignerRole,
+      systemProgram: SystemProgram.programId,
+      rent: rentSysvar
+    }).instruction());
+    assert.equal((await program.account.globalState.fetch(globalState)).pausedFlags, PAUSE_CLAIM);
+
+    const multisigTreasuryMintAccount = getAssociatedTokenAddressSync(treasuryMint, multisigSigner, true);
+    await runProposal(await program.methods.withdrawToken(new anchor.BN(1_000_000)).accounts({
+      owner: multisigSigner,
+      globalState,
+      pool: treasuryPool,
+      vault,
+      tokenMint: treasuryMint,
+      tokenVaultAccount: treasuryVaultAccount,
+      tokenOwnerAccount: multisigTreasuryMintAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: rentSysvar
+    }).instruction());
+    assert.equal((await getAccount(localConnection, multisigTreasuryMintAccount)).amount.toString(), "1000000");
+  });
+});
+
+async function waitForTimestamp(connection, timestamp) {
+  // unix_timestamp of the Clock sysvar sits after slot, epoch_start_timestamp, epoch and leader_schedule_epoch
+  for (;;) {
+    const clock = await connection.getAccountInfo(anchor.web3.SYSVAR_CLOCK_PUBKEY);
+    if (new anchor.BN(clock.data.subarray(32, 40), "le").toNumber() > timestamp) {
+      return;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 500));
+  }
+}
+
+async function createTransferFeeMint(connection, payer, decimals, feeBasisPoints) {
+  const mint = Keypair.generate();
+  const maxFee = BigInt(1_000_000_000);
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+  const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+  await sendAndConfirmTransaction(
+    connection,
+    new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: payer.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: mintLen,
+        lamports,
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeTransferFeeConfigInstruction(
+        mint.publicKey,
+        payer.publicKey,
+        payer.publicKey,
+        feeBasisPoints,
+        maxFee,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeMintInstruction(mint.publicKey, decimals, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    ),
+    [payer, mint]
+  );
+  return mint;
+}
+
+async function createPermanentDelegateMint(connection, payer, decimals) {
+  const mint = Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+  const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+  await sendAndConfirmTransaction(
+    connection,
+    new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: payer.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: mintLen,
+        lamports,
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializePermanentDelegateInstruction(mint.publicKey, payer.publicKey, TOKEN_2022_PROGRAM_ID),
+      createInitializeMintInstruction(mint.publicKey, decimals, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    ),
+    [payer, mint]
+  );
+  return mint;
+}
 
 async function getTokenBalanceWeb3(connection, tokenAccount) {
   const info = await connection.getTokenAccountBalance(tokenAccount);